@@ -0,0 +1,245 @@
+use std::ops::{Index, IndexMut};
+
+/// Opaque handle into an [`Arena`]. Serializes as a plain integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId(usize);
+
+/// One slot in an [`Arena`]: the stored `data` plus its links to
+/// `parent`/`first_child`/`last_child`/`prev_sibling`/`next_sibling`, indextree-style.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node<T> {
+    parent: Option<NodeId>,
+    first_child: Option<NodeId>,
+    last_child: Option<NodeId>,
+    prev_sibling: Option<NodeId>,
+    next_sibling: Option<NodeId>,
+    /// The data this node holds.
+    pub data: T,
+}
+
+impl<T> Node<T> {
+    /// This node's parent, if it was added via [`Arena::append_child`].
+    #[inline]
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// This node's first child, if any.
+    #[inline]
+    pub fn first_child(&self) -> Option<NodeId> {
+        self.first_child
+    }
+
+    /// This node's last child, if any.
+    #[inline]
+    pub fn last_child(&self) -> Option<NodeId> {
+        self.last_child
+    }
+
+    /// The sibling immediately before this node, if any.
+    #[inline]
+    pub fn prev_sibling(&self) -> Option<NodeId> {
+        self.prev_sibling
+    }
+
+    /// The sibling immediately after this node, if any.
+    #[inline]
+    pub fn next_sibling(&self) -> Option<NodeId> {
+        self.next_sibling
+    }
+}
+
+/// An arena-backed tree: nodes are slots in a `Vec`, addressed by [`NodeId`]
+/// and linked via `parent`/`first_child`/`last_child`/`prev_sibling`/`next_sibling`,
+/// so subtrees can be rewritten or walked without recursive ownership.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arena<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        Self { nodes: vec![] }
+    }
+
+    /// Inserts `data` as a new, unlinked node and returns its id.
+    pub fn new_node(&mut self, data: T) -> NodeId {
+        let id = NodeId(self.nodes.len());
+
+        self.nodes.push(Node {
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+            data,
+        });
+
+        id
+    }
+
+    /// Returns the full node slot for `id`, including its tree links.
+    #[inline]
+    pub fn get(&self, id: NodeId) -> &Node<T> {
+        &self.nodes[id.0]
+    }
+
+    /// Returns a mutable reference to the node slot for `id`.
+    #[inline]
+    pub fn get_mut(&mut self, id: NodeId) -> &mut Node<T> {
+        &mut self.nodes[id.0]
+    }
+
+    /// Appends `child` as `parent`'s new last child.
+    pub fn append_child(&mut self, parent: NodeId, child: NodeId) {
+        self.nodes[child.0].parent = Some(parent);
+
+        if let Some(last) = self.nodes[parent.0].last_child {
+            self.nodes[last.0].next_sibling = Some(child);
+            self.nodes[child.0].prev_sibling = Some(last);
+        } else {
+            self.nodes[parent.0].first_child = Some(child);
+        }
+
+        self.nodes[parent.0].last_child = Some(child);
+    }
+
+    /// Links `next` as the sibling immediately following `prev`, without
+    /// assigning either a parent. Used for sequencing nodes that share no
+    /// common container, such as a document's top-level blocks.
+    pub fn link_sibling(&mut self, prev: NodeId, next: NodeId) {
+        self.nodes[prev.0].next_sibling = Some(next);
+        self.nodes[next.0].prev_sibling = Some(prev);
+    }
+
+    /// Iterates `id`'s direct children, in order.
+    pub fn children(&self, id: NodeId) -> Children<'_, T> {
+        Children {
+            arena: self,
+            next: self.nodes[id.0].first_child,
+        }
+    }
+
+    /// Iterates `id` and all its descendants in depth-first, pre-order.
+    pub fn descendants(&self, id: NodeId) -> Descendants<'_, T> {
+        Descendants {
+            arena: self,
+            stack: vec![id],
+        }
+    }
+}
+
+impl<T> Index<NodeId> for Arena<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, id: NodeId) -> &T {
+        &self.nodes[id.0].data
+    }
+}
+
+impl<T> IndexMut<NodeId> for Arena<T> {
+    #[inline]
+    fn index_mut(&mut self, id: NodeId) -> &mut T {
+        &mut self.nodes[id.0].data
+    }
+}
+
+/// Iterator over a node's direct children, yielded by [`Arena::children`].
+pub struct Children<'a, T> {
+    arena: &'a Arena<T>,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> Iterator for Children<'a, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.arena.nodes[id.0].next_sibling;
+        Some(id)
+    }
+}
+
+/// Depth-first, pre-order iterator over a node and its descendants, yielded
+/// by [`Arena::descendants`].
+pub struct Descendants<'a, T> {
+    arena: &'a Arena<T>,
+    stack: Vec<NodeId>,
+}
+
+impl<'a, T> Iterator for Descendants<'a, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.stack.pop()?;
+
+        let mut children = vec![];
+        let mut next = self.arena.nodes[id.0].first_child;
+
+        while let Some(child) = next {
+            next = self.arena.nodes[child.0].next_sibling;
+            children.push(child);
+        }
+
+        self.stack.extend(children.into_iter().rev());
+
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_children() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node("root");
+        let a = arena.new_node("a");
+        let b = arena.new_node("b");
+        let c = arena.new_node("c");
+
+        arena.append_child(root, a);
+        arena.append_child(root, b);
+        arena.append_child(a, c);
+
+        assert_eq!(arena.children(root).collect::<Vec<_>>(), vec![a, b]);
+        assert_eq!(arena.children(a).collect::<Vec<_>>(), vec![c]);
+        assert_eq!(arena.children(b).collect::<Vec<_>>(), vec![]);
+
+        assert_eq!(arena.get(a).parent(), Some(root));
+        assert_eq!(arena.get(b).prev_sibling(), Some(a));
+        assert_eq!(arena.get(a).next_sibling(), Some(b));
+    }
+
+    #[test]
+    fn test_descendants() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node("root");
+        let a = arena.new_node("a");
+        let b = arena.new_node("b");
+        let c = arena.new_node("c");
+
+        arena.append_child(root, a);
+        arena.append_child(root, b);
+        arena.append_child(a, c);
+
+        assert_eq!(
+            arena.descendants(root).collect::<Vec<_>>(),
+            vec![root, a, c, b]
+        );
+    }
+}
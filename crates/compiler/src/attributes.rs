@@ -0,0 +1,324 @@
+use parserc::{ControlFlow, Parser, next, syntax::Syntax, take_while};
+
+use crate::{Kind, MarkDownError, MarkDownInput, ToSource};
+
+#[inline]
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || c == ':'
+}
+
+/// A Djot/Pandoc-style inline attribute block: `{#id .class key=value key="quoted value" %comment%}`.
+///
+/// [`#id`]: sets `id`, overwriting any earlier one.
+/// [`.class`]: appended to `classes`, in source order.
+/// [`key=value`] / [`key="..."`]: appended to `pairs`, in source order.
+/// [`%...%`]: a comment span, discarded.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attributes<I>
+where
+    I: MarkDownInput,
+{
+    /// `#id`.
+    pub id: Option<I>,
+    /// `.class` entries, in source order.
+    pub classes: Vec<I>,
+    /// `key=value` / `key="..."` pairs, in source order.
+    pub pairs: Vec<(I, I)>,
+    /// The full `{...}` slice, including braces.
+    pub raw: I,
+}
+
+impl<I> Attributes<I>
+where
+    I: MarkDownInput,
+{
+    /// Reports how many bytes a candidate attribute block at the front of
+    /// `input` would consume, without allocating or mutating `input`.
+    ///
+    /// Drives the `Start → (Whitespace | ClassFirst/Class | IdFirst/Id | Key →
+    /// ValueFirst → (Value | ValueQuoted)) → Whitespace … → Done | Invalid`
+    /// state machine one token at a time, returning `None` for `Invalid`.
+    pub fn valid(input: &I) -> Option<usize> {
+        let src = input.as_str();
+        let mut chars = src.char_indices().peekable();
+
+        if chars.next().map(|(_, c)| c) != Some('{') {
+            return None;
+        }
+
+        loop {
+            match chars.peek().copied() {
+                Some((i, '}')) => return Some(i + 1),
+                Some((_, '%')) => {
+                    chars.next();
+                    loop {
+                        match chars.next() {
+                            Some((_, '%')) => break,
+                            Some(_) => {}
+                            None => return None,
+                        }
+                    }
+                }
+                Some((_, c)) if c.is_whitespace() => {
+                    chars.next();
+                }
+                Some((_, '#')) | Some((_, '.')) => {
+                    chars.next();
+                    while matches!(chars.peek(), Some((_, c)) if is_name_char(*c)) {
+                        chars.next();
+                    }
+                }
+                Some((_, c)) if is_name_char(c) => {
+                    chars.next();
+                    while matches!(chars.peek(), Some((_, c)) if is_name_char(*c)) {
+                        chars.next();
+                    }
+
+                    // A bare key with no `=value` isn't a `key=value` pair
+                    // `parse` knows how to build; reject it here so `valid`
+                    // and `parse` agree on what's accepted.
+                    if !matches!(chars.peek(), Some((_, '='))) {
+                        return None;
+                    }
+                    chars.next();
+
+                    if matches!(chars.peek(), Some((_, '"'))) {
+                        chars.next();
+                        loop {
+                            match chars.next() {
+                                Some((_, '\\')) => {
+                                    chars.next();
+                                }
+                                Some((_, '"')) => break,
+                                Some(_) => {}
+                                None => return None,
+                            }
+                        }
+                    } else {
+                        while matches!(chars.peek(), Some((_, c)) if is_name_char(*c)) {
+                            chars.next();
+                        }
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// If `content` ends (after trailing whitespace) with a valid attribute
+    /// block, splits it off and returns `(remaining_content, Some(attrs))`;
+    /// otherwise returns `content` unchanged with `None`.
+    pub fn split_trailing(content: I) -> (I, Option<Self>) {
+        let src = content.as_str();
+        let trimmed_len = src.trim_end().len();
+
+        if trimmed_len == 0 || !src[..trimmed_len].ends_with('}') {
+            return (content, None);
+        }
+
+        for (idx, c) in src[..trimmed_len].char_indices().rev() {
+            if c != '{' {
+                continue;
+            }
+
+            let mut probe = content.clone();
+            let candidate = probe.split_off(idx);
+
+            if Self::valid(&candidate) == Some(trimmed_len - idx) {
+                let mut remaining = content;
+                let mut rest = remaining.split_off(idx);
+
+                let attrs =
+                    Self::parse(&mut rest).expect("Safety: block already checked by `valid()`");
+
+                return (remaining, Some(attrs));
+            }
+        }
+
+        (content, None)
+    }
+}
+
+impl<I> Syntax<I> for Attributes<I>
+where
+    I: MarkDownInput,
+{
+    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
+        let Some(len) = Self::valid(input) else {
+            return Err(MarkDownError::Kind(
+                Kind::Attributes,
+                ControlFlow::Recovable,
+                input.to_span(),
+            ));
+        };
+
+        let mut cursor = input.clone();
+        let mut body = cursor.split_to(len);
+        let raw = body.clone();
+
+        next('{').parse(&mut body)?;
+
+        let mut id = None;
+        let mut classes = vec![];
+        let mut pairs = vec![];
+
+        loop {
+            take_while(|c: char| c.is_whitespace()).parse(&mut body)?;
+
+            match body.as_str().chars().next() {
+                Some('}') => {
+                    body.split_to(1);
+                    break;
+                }
+                Some('%') => {
+                    body.split_to(1);
+                    let comment_len = body
+                        .as_str()
+                        .find('%')
+                        .expect("Safety: block already checked by `valid()`");
+                    body.split_to(comment_len);
+                    body.split_to(1);
+                }
+                Some('#') => {
+                    body.split_to(1);
+                    id = Some(take_while(is_name_char).parse(&mut body)?);
+                }
+                Some('.') => {
+                    body.split_to(1);
+                    classes.push(take_while(is_name_char).parse(&mut body)?);
+                }
+                Some(_) => {
+                    let key = take_while(is_name_char).parse(&mut body)?;
+
+                    next('=').parse(&mut body)?;
+
+                    let value = if body.as_str().starts_with('"') {
+                        body.split_to(1);
+
+                        let mut value_len = 0;
+                        let bytes = body.as_str().as_bytes();
+
+                        while bytes[value_len] != b'"' {
+                            if bytes[value_len] == b'\\' {
+                                value_len += 1;
+                            }
+                            value_len += 1;
+                        }
+
+                        let value = body.split_to(value_len);
+                        body.split_to(1);
+                        value
+                    } else {
+                        take_while(is_name_char).parse(&mut body)?
+                    };
+
+                    pairs.push((key, value));
+                }
+                None => unreachable!("Safety: block already checked by `valid()`"),
+            }
+        }
+
+        *input = cursor;
+
+        Ok(Self {
+            id,
+            classes,
+            pairs,
+            raw,
+        })
+    }
+
+    fn to_span(&self) -> parserc::Span {
+        self.raw.to_span()
+    }
+}
+
+impl<I> ToSource for Attributes<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.raw.to_source(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parserc::syntax::InputSyntaxExt;
+
+    use crate::{Attributes, ToSource, TokenStream};
+
+    #[test]
+    fn test_attributes() {
+        assert_eq!(
+            TokenStream::from(r#"{#main .big key=value key2="a b"}"#).parse(),
+            Ok(Attributes {
+                id: Some(TokenStream::from((2, "main"))),
+                classes: vec![TokenStream::from((8, "big"))],
+                pairs: vec![
+                    (
+                        TokenStream::from((12, "key")),
+                        TokenStream::from((16, "value"))
+                    ),
+                    (
+                        TokenStream::from((22, "key2")),
+                        TokenStream::from((28, "a b"))
+                    )
+                ],
+                raw: TokenStream::from(r#"{#main .big key=value key2="a b"}"#),
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("{%just a comment%}").parse(),
+            Ok(Attributes {
+                id: None,
+                classes: vec![],
+                pairs: vec![],
+                raw: TokenStream::from("{%just a comment%}"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_consumes_nothing() {
+        let mut input = TokenStream::from("{#main not closed");
+
+        assert_eq!(Attributes::<TokenStream<'_>>::valid(&input), None);
+        assert!(Attributes::<TokenStream<'_>>::parse(&mut input).is_err());
+        assert_eq!(input, TokenStream::from("{#main not closed"));
+    }
+
+    #[test]
+    fn test_bare_key_rejected() {
+        // A key with no `=value` isn't a pair `parse` can build; `valid`
+        // must reject it the same way `parse` would, so `split_trailing`
+        // never unwraps a `parse` failure on a block `valid` accepted.
+        let mut input = TokenStream::from("{foo}");
+
+        assert_eq!(Attributes::<TokenStream<'_>>::valid(&input), None);
+        assert!(Attributes::<TokenStream<'_>>::parse(&mut input).is_err());
+
+        assert_eq!(
+            Attributes::split_trailing(TokenStream::from("title {foo}")),
+            (TokenStream::from("title {foo}"), None)
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in [
+            r#"{#main .big key=value key2="a b"}"#,
+            "{%just a comment%}",
+        ] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<Attributes<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
+}
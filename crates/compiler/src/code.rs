@@ -3,10 +3,13 @@ use std::cmp;
 use parserc::{
     ControlFlow, Parser, Span, next_if,
     syntax::{InputSyntaxExt, Syntax},
-    take_till, take_until, take_while,
+    take_till, take_while,
 };
 
-use crate::{IndentationFrom, Kind, LineEnding, MarkDownError, MarkDownInput, S};
+use crate::{
+    Attributes, IndentationFrom, IndentationTo, Kind, LineEnding, MarkDownError, MarkDownInput,
+    Needed, S, ToSource,
+};
 
 /// Non-blank lines, each preceded by four or more spaces of indentation.
 ///
@@ -61,12 +64,23 @@ where
     }
 }
 
+impl<I> ToSource for IndentedNonblankLine<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.identation.to_source(out);
+        self.content.to_source(out);
+        self.line_ending.to_source(out);
+    }
+}
+
 /// Blank lines, each preceded by four or more spaces of indentation.
 ///
 /// See [`https://spec.commonmark.org/0.31.2/#fenced-code-blocks`]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Syntax)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[syntax(map_err = Kind::IdentationNonblankChunk.map())]
+#[syntax(map_err = Kind::IdentationBlankChunk.map())]
 pub struct IndentedBlankLine<I>
 where
     I: MarkDownInput,
@@ -90,6 +104,28 @@ where
     Blank(IndentedBlankLine<I>),
 }
 
+impl<I> ToSource for IndentedBlankLine<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.leading_whitespaces.to_source(out);
+        self.line_ending.to_source(out);
+    }
+}
+
+impl<I> ToSource for IdentedChunk<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        match self {
+            IdentedChunk::NonBlank(line) => line.to_source(out),
+            IdentedChunk::Blank(line) => line.to_source(out),
+        }
+    }
+}
+
 ///An [`indented code block`] is composed of one or more indented chunks separated by blank lines.
 ///
 /// [`indented code block`]: https://spec.commonmark.org/0.31.2/#indented-code-block
@@ -99,6 +135,15 @@ pub struct IndentedCodeBlock<I>(pub Vec<IdentedChunk<I>>)
 where
     I: MarkDownInput;
 
+impl<I> ToSource for IndentedCodeBlock<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
 /// Non-empty backtick characters (`) or tildes (~).
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -141,6 +186,41 @@ where
     }
 }
 
+impl<I> ToSource for Fenced<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
+/// Reports whether `line` is a valid closing fence for an opening fence of
+/// `fence_char` repeated at least `min_len` times: up to three leading
+/// spaces, a run of `fence_char` at least as long as the opening fence, and
+/// nothing but spaces/tabs after it.
+///
+/// See [`https://spec.commonmark.org/0.31.2/#fenced-code-blocks`]: "The
+/// closing code fence ... must be at least as long as the opening code
+/// fence, and ... may be followed only by spaces or tabs".
+fn is_closing_fence(line: &str, fence_char: char, min_len: usize) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+
+    if line.len() - trimmed.len() > 3 {
+        return false;
+    }
+
+    let fence_len = trimmed.chars().take_while(|&c| c == fence_char).count();
+
+    if fence_len == 0 || fence_len < min_len {
+        return false;
+    }
+
+    trimmed[fence_len..]
+        .chars()
+        .all(|c| c == ' ' || c == '\t')
+}
+
 /// A [`code fence`] is a sequence of at least three consecutive backtick characters (`) or tildes (~).
 ///
 /// [`code fence`]: https://spec.commonmark.org/0.31.2/#code-fence
@@ -150,59 +230,186 @@ pub struct FencedCodeBlock<I>
 where
     I: MarkDownInput,
 {
+    /// Up to three spaces of indentation are allowed before the opening fence.
+    pub leading_whitespaces: IndentationTo<I, 3>,
     /// Start tag.
     pub start: Fenced<I>,
+    /// Info string: the text between the opening fence and its line ending,
+    /// minus any trailing attribute block. The first word is conventionally
+    /// the code language.
+    pub info: Option<I>,
+    /// Optional Djot/Pandoc-style `{.lang #id key=value}` block trailing the info string.
+    pub attributes: Option<Attributes<I>>,
     /// Code body.
     pub body: I,
     /// End tag.
     pub end: Option<Fenced<I>>,
 }
 
-impl<I> Syntax<I> for FencedCodeBlock<I>
+impl<I> FencedCodeBlock<I>
+where
+    I: MarkDownInput,
+{
+    /// `body` with each line's leading indentation - up to the opening
+    /// fence's own indentation - stripped, per the CommonMark rule that an
+    /// indented opening fence dedents its contents by the same amount.
+    pub fn dedented_body(&self) -> String {
+        let indent = self.leading_whitespaces.0.len();
+
+        if indent == 0 {
+            return self.body.as_str().to_owned();
+        }
+
+        self.body
+            .as_str()
+            .split_inclusive('\n')
+            .map(|line| {
+                let trimmed = line.trim_start_matches(' ');
+                let stripped = (line.len() - trimmed.len()).min(indent);
+                &line[stripped..]
+            })
+            .collect()
+    }
+}
+
+impl<I> FencedCodeBlock<I>
 where
     I: MarkDownInput + 'static,
 {
-    #[inline]
-    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of treating a still-open fence (no closing fence found before
+    /// EOF) as a complete block — the close fence may simply be in the next
+    /// chunk. `is_final` mirrors [`Partial::is_final`]: pass `false` while
+    /// more chunks may still arrive, `true` once the caller has reached true
+    /// EOF (at which point a still-open fence is reported the same way
+    /// [`Syntax::parse`] would).
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        Self::parse_inner(input, is_final)
+    }
+
+    fn parse_inner(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        let leading_whitespaces = IndentationTo::<I, 3>::parse(input)?;
         let start = Fenced::parse(input)?;
 
-        let body = take_until(start.0.clone()).ok().parse(input)?;
+        let fence_char = start
+            .0
+            .as_str()
+            .chars()
+            .next()
+            .expect("Safety: `Fenced` always matches at least one char");
+        let min_len = start.0.len();
+
+        let info_raw = take_till(|c: char| c == '\r' || c == '\n').parse(input)?;
+        let (info_raw, attributes) = Attributes::split_trailing(info_raw);
+        let info = if info_raw.is_empty() {
+            None
+        } else {
+            Some(info_raw)
+        };
 
-        if let Some(body) = body {
-            let end = Fenced::parse(input)?;
+        // Probe line-by-line on a clone for a valid closing fence, so a
+        // mismatched fence character/length or a fence that isn't alone on
+        // its own line is just more body content.
+        let body_from = input.start();
+        let mut scan = input.clone();
+        let mut body_len = None;
 
-            assert_eq!(start.0.len(), end.0.len());
+        loop {
+            if scan.is_empty() {
+                break;
+            }
 
-            Ok(Self {
-                start,
-                body,
-                end: Some(end),
-            })
-        } else {
-            Ok(Self {
+            let line_pos = scan.start();
+            let line = take_till(|c: char| c == '\r' || c == '\n').parse(&mut scan)?;
+
+            if is_closing_fence(line.as_str(), fence_char, min_len) {
+                body_len = Some(line_pos - body_from);
+                break;
+            }
+
+            let line_ending: Option<LineEnding<I>> = scan.parse()?;
+
+            if line_ending.is_none() {
+                break;
+            }
+        }
+
+        let Some(body_len) = body_len else {
+            if !is_final {
+                return Err(MarkDownError::Incomplete(Needed::Unknown));
+            }
+
+            return Ok(Self {
+                leading_whitespaces,
                 start,
+                info,
+                attributes,
                 body: input.split_off(0),
                 end: None,
-            })
-        }
+            });
+        };
+
+        let body = input.split_to(body_len);
+
+        IndentationTo::<I, 3>::parse(input)?;
+        let end = Fenced::parse(input)?;
+        take_while(|c: char| c == ' ' || c == '\t').parse(input)?;
+        let _: Option<LineEnding<I>> = input.parse()?;
+
+        Ok(Self {
+            leading_whitespaces,
+            start,
+            info,
+            attributes,
+            body,
+            end: Some(end),
+        })
+    }
+}
+
+impl<I> Syntax<I> for FencedCodeBlock<I>
+where
+    I: MarkDownInput + 'static,
+{
+    #[inline]
+    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
+        Self::parse_inner(input, true)
     }
 
     #[inline]
     fn to_span(&self) -> Span {
-        self.start
+        self.leading_whitespaces
             .to_span()
+            .union(&self.start.to_span())
+            .union(&self.info.to_span())
+            .union(&self.attributes.to_span())
             .union(&self.body.to_span())
             .union(&self.end.to_span())
     }
 }
 
+impl<I> ToSource for FencedCodeBlock<I>
+where
+    I: MarkDownInput + 'static,
+{
+    fn to_source(&self, out: &mut String) {
+        self.leading_whitespaces.to_source(out);
+        self.start.to_source(out);
+        self.info.to_source(out);
+        self.attributes.to_source(out);
+        self.body.to_source(out);
+        self.end.to_source(out);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use parserc::{ControlFlow, Span, syntax::InputSyntaxExt};
 
     use crate::{
-        Fenced, FencedCodeBlock, IdentedChunk, IndentationFrom, IndentedBlankLine,
-        IndentedCodeBlock, IndentedNonblankLine, Kind, LineEnding, MarkDownError, S, TokenStream,
+        Attributes, Fenced, FencedCodeBlock, IdentedChunk, IndentationFrom, IndentationTo,
+        IndentedBlankLine, IndentedCodeBlock, IndentedNonblankLine, Kind, LineEnding,
+        MarkDownError, Needed, S, ToSource, TokenStream,
     };
 
     #[test]
@@ -226,7 +433,7 @@ mod tests {
         assert_eq!(
             TokenStream::from("hello\r\n").parse::<IndentedBlankLine<_>>(),
             Err(MarkDownError::Kind(
-                Kind::IdentationNonblankChunk,
+                Kind::IdentationBlankChunk,
                 ControlFlow::Recovable,
                 Span::Range(0..1)
             ))
@@ -330,7 +537,10 @@ mod tests {
         assert_eq!(
             TokenStream::from("~~~~\naaa\n~~~\n~~~~",).parse(),
             Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
                 start: Fenced(TokenStream::from("~~~~")),
+                info: None,
+                attributes: None,
                 body: TokenStream::from((4, "\naaa\n~~~\n")),
                 end: Some(Fenced(TokenStream::from((13, "~~~~"))))
             })
@@ -339,10 +549,135 @@ mod tests {
         assert_eq!(
             TokenStream::from("~~~~\naaa\n~~~\n",).parse(),
             Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
                 start: Fenced(TokenStream::from("~~~~")),
+                info: None,
+                attributes: None,
                 body: TokenStream::from((4, "\naaa\n~~~\n")),
                 end: None
             })
         );
+
+        assert_eq!(
+            TokenStream::from("```rust\nfn main() {}\n```",).parse(),
+            Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
+                start: Fenced(TokenStream::from("```")),
+                info: Some(TokenStream::from((3, "rust"))),
+                attributes: None,
+                body: TokenStream::from((7, "\nfn main() {}\n")),
+                end: Some(Fenced(TokenStream::from((21, "```"))))
+            })
+        );
+
+        // The closing fence must be at least as long as the opening one.
+        assert_eq!(
+            TokenStream::from("````\naaa\n```\n````",).parse(),
+            Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
+                start: Fenced(TokenStream::from("````")),
+                info: None,
+                attributes: None,
+                body: TokenStream::from((4, "\naaa\n```\n")),
+                end: Some(Fenced(TokenStream::from((13, "````"))))
+            })
+        );
+
+        // Tildes cannot close a backtick fence.
+        assert_eq!(
+            TokenStream::from("```\naaa\n~~~\n```",).parse(),
+            Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
+                start: Fenced(TokenStream::from("```")),
+                info: None,
+                attributes: None,
+                body: TokenStream::from((3, "\naaa\n~~~\n")),
+                end: Some(Fenced(TokenStream::from((12, "```"))))
+            })
+        );
+
+        // A trailing `{...}` on the info line is an attribute block, not part of `info`.
+        assert_eq!(
+            TokenStream::from("```{.rust #main}\nfn main() {}\n```",).parse(),
+            Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
+                start: Fenced(TokenStream::from("```")),
+                info: None,
+                attributes: Some(Attributes {
+                    id: Some(TokenStream::from((11, "main"))),
+                    classes: vec![TokenStream::from((5, "rust"))],
+                    pairs: vec![],
+                    raw: TokenStream::from((3, "{.rust #main}")),
+                }),
+                body: TokenStream::from((16, "\nfn main() {}\n")),
+                end: Some(Fenced(TokenStream::from((30, "```"))))
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in [
+            "~~~~\naaa\n~~~\n~~~~",
+            "~~~~\naaa\n~~~\n",
+            "```rust\nfn main() {}\n```",
+            "```{.rust #main}\nfn main() {}\n```",
+        ] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<FencedCodeBlock<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+
+        for raw in ["     helle world\n", "     helle\n\n   \n    world\n"] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<IndentedCodeBlock<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_fenced_code_block_parse_partial() {
+        // No closing fence has arrived yet: it may still be in the next chunk.
+        assert_eq!(
+            FencedCodeBlock::parse_partial(&mut TokenStream::from("```\naaa\n"), false),
+            Err(MarkDownError::Incomplete(Needed::Unknown))
+        );
+
+        // Once the caller has reached true EOF, the same input is reported
+        // the way `Syntax::parse` would report it - a complete block with no
+        // closing fence.
+        assert_eq!(
+            FencedCodeBlock::parse_partial(&mut TokenStream::from("```\naaa\n"), true),
+            Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
+                start: Fenced(TokenStream::from("```")),
+                info: None,
+                attributes: None,
+                body: TokenStream::from((3, "\naaa\n")),
+                end: None
+            })
+        );
+
+        // Once the closing fence has arrived, partial mode parses identically
+        // to `Syntax::parse`.
+        assert_eq!(
+            FencedCodeBlock::parse_partial(&mut TokenStream::from("```\naaa\n```\n"), false),
+            Ok(FencedCodeBlock {
+                leading_whitespaces: IndentationTo(TokenStream::from("")),
+                start: Fenced(TokenStream::from("```")),
+                info: None,
+                attributes: None,
+                body: TokenStream::from((3, "\naaa\n")),
+                end: Some(Fenced(TokenStream::from((8, "```"))))
+            })
+        );
     }
 }
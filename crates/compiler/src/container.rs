@@ -0,0 +1,422 @@
+use std::marker::PhantomData;
+
+use parserc::{
+    ControlFlow, Parser, Span,
+    syntax::{InputSyntaxExt, Syntax},
+    take_till, take_while,
+};
+
+use crate::{Kind, LineEnding, MarkDownError, MarkDownInput, Needed, ToSource};
+
+/// The keyword pair a [`ContainerBlock`] is parameterized by, e.g. org-mode's
+/// `#+BEGIN_`/`#+END_`.
+///
+/// Matching the open keyword, and matching a candidate closing line against
+/// the close keyword plus the block's own name, is case-insensitive, per
+/// org-mode convention.
+pub trait ContainerDelimiters {
+    /// Keyword introducing the opening line, immediately before the block name.
+    const OPEN: &'static str;
+    /// Keyword introducing the closing line, immediately before the block name.
+    const CLOSE: &'static str;
+}
+
+/// A generic named container block: `open-delimiter name info-line`,
+/// followed by its contents, followed by a `close-delimiter name` line - the
+/// shape org-mode's `#+BEGIN_name args ... #+END_name` uses, and the shared
+/// engine a fenced code block or a markdown extension directive can build
+/// on instead of re-implementing fence scanning and blank-line accounting
+/// each time.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContainerBlock<I, D>
+where
+    I: MarkDownInput,
+    D: ContainerDelimiters,
+{
+    /// The open delimiter itself, e.g. `#+BEGIN_` in `#+BEGIN_SRC` - kept
+    /// verbatim since matching it against [`ContainerDelimiters::OPEN`] is
+    /// case-insensitive.
+    pub open: I,
+    /// The block name following the open delimiter, e.g. `SRC` in `#+BEGIN_SRC`.
+    pub name: I,
+    /// Whitespace separating the name from the info string.
+    pub seperate: I,
+    /// The rest of the opening line after the name: an info/arguments string.
+    pub info: Option<I>,
+    /// Line ending closing the opening line.
+    pub open_line_ending: Option<LineEnding<I>>,
+    /// Count of blank lines immediately following the opening line, before
+    /// the first line of actual content.
+    pub pre_blank: usize,
+    /// Every line between the opening and closing delimiter lines, verbatim.
+    pub contents: I,
+    /// [`ContainerBlock::contents`] with its `pre_blank` leading blank lines
+    /// stripped.
+    pub contents_without_leading_blanks: I,
+    /// Count of blank lines immediately preceding the closing delimiter line.
+    pub post_blank: usize,
+    /// The closing delimiter line, or `None` if the block ran to EOF with no
+    /// matching close.
+    pub close: Option<I>,
+    /// Line ending closing the close line; `None` when [`Self::close`] is,
+    /// or when the close line ends the input without a line ending.
+    pub close_line_ending: Option<LineEnding<I>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _delimiters: PhantomData<D>,
+}
+
+/// Reports whether `line` is a matching close delimiter line for `name`:
+/// optional leading spaces/tabs, `close_keyword` followed immediately by
+/// `name` (both case-insensitive), and nothing after it but trailing
+/// spaces/tabs.
+fn is_closing_line(line: &str, close_keyword: &str, name: &str) -> bool {
+    let trimmed = line.trim_start_matches([' ', '\t']);
+
+    if trimmed.len() < close_keyword.len()
+        || !trimmed[..close_keyword.len()].eq_ignore_ascii_case(close_keyword)
+    {
+        return false;
+    }
+
+    let rest = &trimmed[close_keyword.len()..];
+
+    if rest.len() < name.len() || !rest[..name.len()].eq_ignore_ascii_case(name) {
+        return false;
+    }
+
+    rest[name.len()..].trim().is_empty()
+}
+
+/// Splits `text` into lines (each kept with its own trailing line ending)
+/// and reports `(pre_blank, post_blank, pre_blank_len)`: the number of
+/// leading/trailing whitespace-only lines, and the byte length of the
+/// leading blank run - the offset at which
+/// [`ContainerBlock::contents_without_leading_blanks`] begins.
+fn blank_run_bounds(text: &str) -> (usize, usize, usize) {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+
+    let is_blank = |line: &&str| line.trim().is_empty();
+
+    let pre_blank = lines.iter().take_while(is_blank).count();
+    let post_blank = lines.iter().rev().take_while(is_blank).count().min(lines.len() - pre_blank);
+    let pre_blank_len = lines.iter().take(pre_blank).map(|line| line.len()).sum();
+
+    (pre_blank, post_blank, pre_blank_len)
+}
+
+impl<I, D> ContainerBlock<I, D>
+where
+    I: MarkDownInput,
+    D: ContainerDelimiters,
+{
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of treating a still-open block (no closing line found before
+    /// EOF) as complete - the close line may simply be in the next chunk.
+    /// `is_final` mirrors [`Partial::is_final`]: pass `false` while more
+    /// chunks may still arrive, `true` once the caller has reached true EOF.
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        Self::parse_inner(input, is_final)
+    }
+
+    fn parse_inner(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        if input.as_str().len() < D::OPEN.len()
+            || !input.as_str()[..D::OPEN.len()].eq_ignore_ascii_case(D::OPEN)
+        {
+            return Err(MarkDownError::Kind(
+                Kind::ContainerBlock,
+                ControlFlow::Recovable,
+                input.to_span(),
+            ));
+        }
+
+        let open = input.split_to(D::OPEN.len());
+
+        let name = take_while(|c: char| c.is_alphanumeric() || c == '_' || c == '-').parse(input)?;
+
+        if name.is_empty() {
+            return Err(MarkDownError::Kind(
+                Kind::ContainerBlock,
+                ControlFlow::Recovable,
+                input.to_span(),
+            ));
+        }
+
+        let seperate = take_while(|c: char| c == ' ' || c == '\t').parse(input)?;
+
+        let info_raw = take_till(|c: char| c == '\r' || c == '\n').parse(input)?;
+        let info = if info_raw.is_empty() { None } else { Some(info_raw) };
+
+        let open_line_ending: Option<LineEnding<I>> = input.parse()?;
+
+        // Probe line-by-line on a clone for a matching closing line, so a
+        // line that merely starts with the close keyword but names a
+        // different block is just more content.
+        let content_from = input.start();
+        let mut scan = input.clone();
+        let mut close_len = None;
+
+        loop {
+            if scan.is_empty() {
+                break;
+            }
+
+            let line_pos = scan.start();
+            let line = take_till(|c: char| c == '\r' || c == '\n').parse(&mut scan)?;
+
+            if is_closing_line(line.as_str(), D::CLOSE, name.as_str()) {
+                close_len = Some(line_pos - content_from);
+                break;
+            }
+
+            let line_ending: Option<LineEnding<I>> = scan.parse()?;
+
+            if line_ending.is_none() {
+                break;
+            }
+        }
+
+        let Some(close_len) = close_len else {
+            if !is_final {
+                return Err(MarkDownError::Incomplete(Needed::Unknown));
+            }
+
+            let contents = input.split_off(0);
+            let (pre_blank, post_blank, pre_blank_len) = blank_run_bounds(contents.as_str());
+            let mut contents_without_leading_blanks = contents.clone();
+            contents_without_leading_blanks.split_to(pre_blank_len);
+
+            return Ok(Self {
+                open,
+                name,
+                seperate,
+                info,
+                open_line_ending,
+                pre_blank,
+                contents,
+                contents_without_leading_blanks,
+                post_blank,
+                close: None,
+                close_line_ending: None,
+                _delimiters: PhantomData,
+            });
+        };
+
+        let contents = input.split_to(close_len);
+        let (pre_blank, post_blank, pre_blank_len) = blank_run_bounds(contents.as_str());
+        let mut contents_without_leading_blanks = contents.clone();
+        contents_without_leading_blanks.split_to(pre_blank_len);
+
+        let close = take_till(|c: char| c == '\r' || c == '\n').parse(input)?;
+        let close_line_ending: Option<LineEnding<I>> = input.parse()?;
+
+        Ok(Self {
+            open,
+            name,
+            seperate,
+            info,
+            open_line_ending,
+            pre_blank,
+            contents,
+            contents_without_leading_blanks,
+            post_blank,
+            close: Some(close),
+            close_line_ending,
+            _delimiters: PhantomData,
+        })
+    }
+}
+
+impl<I, D> Syntax<I> for ContainerBlock<I, D>
+where
+    I: MarkDownInput,
+    D: ContainerDelimiters,
+{
+    #[inline]
+    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
+        Self::parse_inner(input, true)
+    }
+
+    #[inline]
+    fn to_span(&self) -> Span {
+        self.open
+            .to_span()
+            .union(&self.name.to_span())
+            .union(&self.seperate.to_span())
+            .union(&self.info.to_span())
+            .union(&self.open_line_ending.to_span())
+            .union(&self.contents.to_span())
+            .union(&self.close.to_span())
+            .union(&self.close_line_ending.to_span())
+    }
+}
+
+impl<I, D> ToSource for ContainerBlock<I, D>
+where
+    I: MarkDownInput,
+    D: ContainerDelimiters,
+{
+    fn to_source(&self, out: &mut String) {
+        self.open.to_source(out);
+        self.name.to_source(out);
+        self.seperate.to_source(out);
+        self.info.to_source(out);
+        self.open_line_ending.to_source(out);
+        self.contents.to_source(out);
+        self.close.to_source(out);
+        self.close_line_ending.to_source(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parserc::{ControlFlow, Span, syntax::InputSyntaxExt};
+
+    use crate::{
+        ContainerBlock, ContainerDelimiters, Kind, LineEnding, MarkDownError, Needed, ToSource,
+        TokenStream,
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    struct OrgBlock;
+
+    impl ContainerDelimiters for OrgBlock {
+        const OPEN: &'static str = "#+BEGIN_";
+        const CLOSE: &'static str = "#+END_";
+    }
+
+    #[test]
+    fn test_container_block() {
+        assert_eq!(
+            TokenStream::from("#+BEGIN_SRC rust\nfn main() {}\n#+END_SRC\n").parse(),
+            Ok(ContainerBlock::<_, OrgBlock> {
+                open: TokenStream::from("#+BEGIN_"),
+                name: TokenStream::from((8, "SRC")),
+                seperate: TokenStream::from((11, " ")),
+                info: Some(TokenStream::from((12, "rust"))),
+                open_line_ending: Some(LineEnding::LF(TokenStream::from((16, "\n")))),
+                pre_blank: 0,
+                contents: TokenStream::from((17, "fn main() {}\n")),
+                contents_without_leading_blanks: TokenStream::from((17, "fn main() {}\n")),
+                post_blank: 0,
+                close: Some(TokenStream::from((30, "#+END_SRC"))),
+                close_line_ending: Some(LineEnding::LF(TokenStream::from((39, "\n")))),
+                _delimiters: std::marker::PhantomData,
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("#+BEGIN_SRC\n\n\nfn main() {}\n\n#+END_SRC").parse(),
+            Ok(ContainerBlock::<_, OrgBlock> {
+                open: TokenStream::from("#+BEGIN_"),
+                name: TokenStream::from((8, "SRC")),
+                seperate: TokenStream::from((11, "")),
+                info: None,
+                open_line_ending: Some(LineEnding::LF(TokenStream::from((11, "\n")))),
+                pre_blank: 2,
+                contents: TokenStream::from((12, "\n\nfn main() {}\n\n")),
+                contents_without_leading_blanks: TokenStream::from((14, "fn main() {}\n\n")),
+                post_blank: 1,
+                close: Some(TokenStream::from((28, "#+END_SRC"))),
+                close_line_ending: None,
+                _delimiters: std::marker::PhantomData,
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("#+BEGIN_SRC\nfn main() {}\n").parse::<ContainerBlock<_, OrgBlock>>(),
+            Ok(ContainerBlock {
+                open: TokenStream::from("#+BEGIN_"),
+                name: TokenStream::from((8, "SRC")),
+                seperate: TokenStream::from((11, "")),
+                info: None,
+                open_line_ending: Some(LineEnding::LF(TokenStream::from((11, "\n")))),
+                pre_blank: 0,
+                contents: TokenStream::from((12, "fn main() {}\n")),
+                contents_without_leading_blanks: TokenStream::from((12, "fn main() {}\n")),
+                post_blank: 0,
+                close: None,
+                close_line_ending: None,
+                _delimiters: std::marker::PhantomData,
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("hello world").parse::<ContainerBlock<_, OrgBlock>>(),
+            Err(MarkDownError::Kind(
+                Kind::ContainerBlock,
+                ControlFlow::Recovable,
+                Span::Range(0..11)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_container_block_parse_partial() {
+        assert_eq!(
+            ContainerBlock::<_, OrgBlock>::parse_partial(
+                &mut TokenStream::from("#+BEGIN_SRC\nfn main() {}\n"),
+                false
+            ),
+            Err(MarkDownError::Incomplete(Needed::Unknown))
+        );
+
+        assert_eq!(
+            ContainerBlock::<_, OrgBlock>::parse_partial(
+                &mut TokenStream::from("#+BEGIN_SRC\nfn main() {}\n"),
+                true
+            ),
+            Ok(ContainerBlock {
+                open: TokenStream::from("#+BEGIN_"),
+                name: TokenStream::from((8, "SRC")),
+                seperate: TokenStream::from((11, "")),
+                info: None,
+                open_line_ending: Some(LineEnding::LF(TokenStream::from((11, "\n")))),
+                pre_blank: 0,
+                contents: TokenStream::from((12, "fn main() {}\n")),
+                contents_without_leading_blanks: TokenStream::from((12, "fn main() {}\n")),
+                post_blank: 0,
+                close: None,
+                close_line_ending: None,
+                _delimiters: std::marker::PhantomData,
+            })
+        );
+
+        assert_eq!(
+            ContainerBlock::<_, OrgBlock>::parse_partial(
+                &mut TokenStream::from("#+BEGIN_SRC\nfn main() {}\n#+END_SRC\n"),
+                false
+            ),
+            Ok(ContainerBlock {
+                open: TokenStream::from("#+BEGIN_"),
+                name: TokenStream::from((8, "SRC")),
+                seperate: TokenStream::from((11, "")),
+                info: None,
+                open_line_ending: Some(LineEnding::LF(TokenStream::from((11, "\n")))),
+                pre_blank: 0,
+                contents: TokenStream::from((12, "fn main() {}\n")),
+                contents_without_leading_blanks: TokenStream::from((12, "fn main() {}\n")),
+                post_blank: 0,
+                close: Some(TokenStream::from((25, "#+END_SRC"))),
+                close_line_ending: Some(LineEnding::LF(TokenStream::from((34, "\n")))),
+                _delimiters: std::marker::PhantomData,
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in [
+            "#+BEGIN_SRC rust\nfn main() {}\n#+END_SRC\n",
+            "#+BEGIN_SRC\n\n\nfn main() {}\n\n#+END_SRC",
+        ] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<ContainerBlock<_, OrgBlock>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use parserc::{
+    ControlFlow, ParseError, Parser,
+    syntax::{InputSyntaxExt, Syntax},
+    take_till,
+};
+
+use crate::{
+    ATXHeading, Arena, BlankLine, FencedCodeBlock, IndentedCodeBlock, Kind, LineEnding,
+    MarkDownError, MarkDownInput, NodeId, ThematicBreaks, ToSource,
+};
+
+/// One block-level construct, and a single node's payload in a [`Document`]'s
+/// arena tree.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Block<I>
+where
+    I: MarkDownInput,
+{
+    Blank(BlankLine<I>),
+    Heading(ATXHeading<I>),
+    Thematic(ThematicBreaks<I>),
+    FencedCode(FencedCodeBlock<I>),
+    IndentedCode(IndentedCodeBlock<I>),
+}
+
+impl<I> Block<I>
+where
+    I: MarkDownInput + 'static,
+{
+    /// The span of the underlying construct.
+    pub fn to_span(&self) -> parserc::Span {
+        match self {
+            Block::Blank(block) => block.to_span(),
+            Block::Heading(block) => block.to_span(),
+            Block::Thematic(block) => block.to_span(),
+            Block::FencedCode(block) => block.to_span(),
+            Block::IndentedCode(block) => block.to_span(),
+        }
+    }
+}
+
+impl<I> ToSource for Block<I>
+where
+    I: MarkDownInput + 'static,
+{
+    fn to_source(&self, out: &mut String) {
+        match self {
+            Block::Blank(block) => block.to_source(out),
+            Block::Heading(block) => block.to_source(out),
+            Block::Thematic(block) => block.to_source(out),
+            Block::FencedCode(block) => block.to_source(out),
+            Block::IndentedCode(block) => block.to_source(out),
+        }
+    }
+}
+
+/// Attempts `T::parse` on a clone of `input`, committing the advance to
+/// `input` only if it succeeds.
+fn try_parse<T, I>(input: &mut I) -> Option<T>
+where
+    T: Syntax<I>,
+    I: MarkDownInput,
+{
+    let mut probe = input.clone();
+
+    match T::parse(&mut probe) {
+        Ok(value) => {
+            *input = probe;
+            Some(value)
+        }
+        Err(_) => None,
+    }
+}
+
+/// Advances `input` past the rest of the current line: everything up to the
+/// next `\r`/`\n`, plus the line ending itself if one is present. Used by
+/// [`Document::parse_recovering`] to guarantee forward progress past a line
+/// that couldn't be parsed as any block.
+fn skip_line<I>(input: &mut I)
+where
+    I: MarkDownInput,
+{
+    let _ = take_till(|c: char| c == '\r' || c == '\n').parse(input);
+    let _: Option<LineEnding<I>> = input.parse().ok();
+}
+
+/// A whole markdown document, sequenced block by block into an
+/// [`Arena`]-backed tree.
+///
+/// Today's block parsers never nest, so every block in `top_level` is a
+/// sibling of the others with no parent node; the arena's `parent`/`first_child`
+/// links exist so a future container block (block quotes, lists) can attach
+/// its contents with [`Arena::append_child`] without changing this shape.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Document<I>
+where
+    I: MarkDownInput,
+{
+    /// The arena backing every block in this document.
+    pub arena: Arena<Block<I>>,
+    /// The document's blocks, in source order.
+    pub top_level: Vec<NodeId>,
+}
+
+impl<I> Document<I>
+where
+    I: MarkDownInput,
+{
+    /// Iterates every block in the document, depth-first pre-order: each
+    /// top-level block followed by its descendants before moving to the next
+    /// top-level block.
+    pub fn descendants(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.top_level
+            .iter()
+            .flat_map(move |id| self.arena.descendants(*id))
+    }
+}
+
+impl<I> Syntax<I> for Document<I>
+where
+    I: MarkDownInput + 'static,
+{
+    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
+        let mut arena = Arena::new();
+        let mut top_level = vec![];
+        let mut last: Option<NodeId> = None;
+
+        while !input.is_empty() {
+            let before = input.start();
+
+            let block = if let Some(block) = try_parse::<BlankLine<I>, _>(input) {
+                Block::Blank(block)
+            } else if let Some(block) = try_parse::<ATXHeading<I>, _>(input) {
+                Block::Heading(block)
+            } else if let Some(block) = try_parse::<ThematicBreaks<I>, _>(input) {
+                Block::Thematic(block)
+            } else if let Some(block) = try_parse::<FencedCodeBlock<I>, _>(input) {
+                Block::FencedCode(block)
+            } else if let Some(block) = try_parse::<IndentedCodeBlock<I>, _>(input) {
+                Block::IndentedCode(block)
+            } else {
+                return Err(MarkDownError::Kind(
+                    Kind::Document,
+                    ControlFlow::Fatal,
+                    input.to_span(),
+                ));
+            };
+
+            if input.start() == before {
+                return Err(MarkDownError::Kind(
+                    Kind::Document,
+                    ControlFlow::Fatal,
+                    input.to_span(),
+                ));
+            }
+
+            let node = arena.new_node(block);
+
+            if let Some(last) = last {
+                arena.link_sibling(last, node);
+            }
+
+            top_level.push(node);
+            last = Some(node);
+        }
+
+        Ok(Self { arena, top_level })
+    }
+
+    fn to_span(&self) -> parserc::Span {
+        self.top_level
+            .iter()
+            .fold(parserc::Span::None, |span, id| {
+                span.union(&self.arena[*id].to_span())
+            })
+    }
+}
+
+impl<I> Document<I>
+where
+    I: MarkDownInput + 'static,
+{
+    /// Like [`Syntax::parse`], but follows rustc's approach of recovering
+    /// instead of aborting the whole document: [`BlankLine`],
+    /// [`ATXHeading`], [`FencedCodeBlock`] and [`IndentedCodeBlock`] are
+    /// probed in turn, and when none of them matches, [`ThematicBreaks`] is
+    /// tried last. If it fails with [`ControlFlow::Recovable`], its error is
+    /// recorded, the cursor is advanced past the offending line with
+    /// [`skip_line`], and parsing resumes with the next block. This
+    /// surfaces every problem in a document in one pass instead of only
+    /// the first.
+    ///
+    /// A [`ControlFlow::Fatal`] error from [`ThematicBreaks`] is recorded
+    /// and ends the loop. Errors from the other block parsers, fatal or
+    /// not, are discarded, as in [`Syntax::parse`]: they only move on to
+    /// the next parser.
+    ///
+    /// Unlike [`Syntax::parse`], this doesn't build an [`Arena`] tree: it
+    /// returns the best-effort blocks in source order alongside every
+    /// diagnostic collected along the way.
+    pub fn parse_recovering(input: &mut I) -> (Vec<Block<I>>, Vec<MarkDownError>) {
+        let mut blocks = vec![];
+        let mut errors = vec![];
+
+        while !input.is_empty() {
+            let before = input.start();
+
+            if let Some(block) = try_parse::<BlankLine<I>, _>(input) {
+                blocks.push(Block::Blank(block));
+            } else if let Some(block) = try_parse::<ATXHeading<I>, _>(input) {
+                blocks.push(Block::Heading(block));
+            } else if let Some(block) = try_parse::<FencedCodeBlock<I>, _>(input) {
+                blocks.push(Block::FencedCode(block));
+            } else if let Some(block) = try_parse::<IndentedCodeBlock<I>, _>(input) {
+                blocks.push(Block::IndentedCode(block));
+            } else {
+                let mut probe = input.clone();
+
+                match ThematicBreaks::parse(&mut probe) {
+                    Ok(block) => {
+                        *input = probe;
+                        blocks.push(Block::Thematic(block));
+                    }
+                    Err(err) => match err.control_flow() {
+                        ControlFlow::Fatal => {
+                            errors.push(err);
+                            break;
+                        }
+                        ControlFlow::Recovable => {
+                            errors.push(err);
+                            skip_line(input);
+                        }
+                    },
+                }
+            }
+
+            if input.start() == before {
+                // Defensive: ends the loop, rather than spinning on the same
+                // position, if a block above somehow matched zero bytes.
+                break;
+            }
+        }
+
+        (blocks, errors)
+    }
+}
+
+impl<I> ToSource for Document<I>
+where
+    I: MarkDownInput + 'static,
+{
+    fn to_source(&self, out: &mut String) {
+        for id in &self.top_level {
+            self.arena[*id].to_source(out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parserc::{
+        Span,
+        syntax::{InputSyntaxExt, Punctuated},
+    };
+
+    use crate::{
+        ATXHeading, BlankLine, Block, Document, IndentationTo, LineEnding, MarkDownError,
+        ThematicBreaks, ThematicChars, ToSource, TokenStream,
+    };
+
+    #[test]
+    fn test_document() {
+        let doc: Document<_> = TokenStream::from("# Hi\n\n---\n").parse().unwrap();
+
+        assert_eq!(doc.top_level.len(), 3);
+
+        assert_eq!(
+            doc.arena[doc.top_level[0]],
+            Block::Heading(ATXHeading {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                leading_pounds: TokenStream::from("#"),
+                seperate: TokenStream::from((1, " ")),
+                content: TokenStream::from((2, "Hi")),
+                attributes: None,
+                line_ending: Some(LineEnding::LF(TokenStream::from((4, "\n"))))
+            })
+        );
+
+        assert_eq!(
+            doc.arena[doc.top_level[1]],
+            Block::Blank(BlankLine(TokenStream::from((5, "\n"))))
+        );
+
+        assert_eq!(
+            doc.arena[doc.top_level[2]],
+            Block::Thematic(ThematicBreaks {
+                ident_whitespaces: IndentationTo(TokenStream::from((6, ""))),
+                breaks: Punctuated {
+                    pairs: vec![],
+                    tail: Some(Box::new(ThematicChars::Minus(TokenStream::from((6, "---")))))
+                },
+                line_ending: Some(LineEnding::LF(TokenStream::from((9, "\n"))))
+            })
+        );
+
+        // Siblings are linked even though none of these blocks share a parent.
+        assert_eq!(
+            doc.arena.get(doc.top_level[0]).next_sibling(),
+            Some(doc.top_level[1])
+        );
+        assert_eq!(
+            doc.arena.get(doc.top_level[2]).prev_sibling(),
+            Some(doc.top_level[1])
+        );
+
+        assert_eq!(
+            doc.descendants().collect::<Vec<_>>(),
+            doc.top_level.clone()
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in ["# Hi\n\n---\n", "###### hello {#intro .big}\r\n"] {
+            let doc: Document<_> = TokenStream::from(raw).parse().unwrap();
+
+            assert_eq!(doc.to_source_string(), raw);
+        }
+    }
+
+    #[test]
+    fn test_parse_recovering() {
+        // The first line is a too-short thematic break (recoverable
+        // diagnostic): it's recorded as an error and skipped rather than
+        // aborting the whole document.
+        let mut input = TokenStream::from("- -\n\n---\n");
+
+        let (blocks, errors) = Document::parse_recovering(&mut input);
+
+        assert_eq!(blocks.len(), 2);
+        assert!(matches!(blocks[0], Block::Blank(_)));
+        assert!(matches!(blocks[1], Block::Thematic(_)));
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            MarkDownError::Diagnostic(diagnostic) => {
+                assert_eq!(diagnostic.primary, Span::Range(0..3));
+                assert!(diagnostic.suggestion.is_some());
+            }
+            other => panic!("expected a diagnostic, got {other:?}"),
+        }
+
+        // Parsing resumed all the way to the end despite the first line
+        // failing.
+        assert!(input.is_empty());
+    }
+}
@@ -3,7 +3,7 @@ use std::{cmp, collections::HashSet, sync::LazyLock};
 use entities::ENTITIES;
 use parserc::{ControlFlow, ParseError, Parser, Span, next, syntax::Syntax};
 
-use crate::{Kind, MarkDownError, MarkDownInput};
+use crate::{Kind, MarkDownError, MarkDownInput, Needed, ToSource};
 
 /// Valid entity names.
 #[allow(unused)]
@@ -12,36 +12,71 @@ static NAMES: LazyLock<HashSet<&'static str>> =
 
 static MAX_ENTITY_LEN: usize = 100;
 
-/// HTML5 entity characters
+/// Maximum count of decimal digits accepted in a `&#...;` reference.
+static MAX_DECIMAL_DIGITS: usize = 7;
+
+/// Maximum count of hex digits accepted in a `&#x...;` reference.
+static MAX_HEX_DIGITS: usize = 6;
+
+/// Resolve a numeric character reference's codepoint value to a `char`.
+///
+/// See [`https://spec.commonmark.org/0.31.2/#entity-and-numeric-character-references`]:
+/// codepoint `0`, values above `0x10FFFF`, and the surrogate range must resolve
+/// to the replacement character rather than error.
+fn resolve_codepoint(value: u32) -> char {
+    if value == 0 || value > 0x10FFFF || (0xD800..=0xDFFF).contains(&value) {
+        '\u{FFFD}'
+    } else {
+        char::from_u32(value).unwrap_or('\u{FFFD}')
+    }
+}
+
+/// HTML5 entity characters: named references, and decimal/hexadecimal
+/// numeric character references.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Entity<I>(pub I)
+pub enum Entity<I>
 where
-    I: MarkDownInput;
+    I: MarkDownInput,
+{
+    /// `&name;`, where `name` is a member of the html5 entity table.
+    Named(I),
+    /// `&#digits;`, carrying the resolved codepoint alongside the raw slice.
+    Decimal { raw: I, value: char },
+    /// `&#xhex;` / `&#Xhex;`, carrying the resolved codepoint alongside the raw slice.
+    Hex { raw: I, value: char },
+}
 
-impl<I> Syntax<I> for Entity<I>
+impl<I> Entity<I>
 where
     I: MarkDownInput,
 {
-    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
-        next('&')
-            .parse(&mut input.clone())
-            .map_err(|err| MarkDownError::Kind(Kind::Entity, err.control_flow(), err.span()))?;
-
+    fn parse_named(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        // `;` is ASCII, so scanning the raw bytes and never decoding to `char`
+        // is safe here: a byte equal to `;` can never be the interior of a
+        // wider UTF-8 codepoint.
+        let bytes = input.as_str().as_bytes();
         let mut last = None;
 
-        for (index, c) in input.iter_indices() {
+        for (index, &b) in bytes.iter().enumerate() {
             if index > MAX_ENTITY_LEN {
                 break;
             }
 
-            if c == ';' {
+            if b == b';' {
                 last = Some(index + 1);
                 break;
             }
         }
 
         let Some(last) = last else {
+            // The whole buffer was scanned without finding `;`. If it's
+            // shorter than the length budget, a later chunk could still
+            // supply `;` before the budget is exceeded.
+            if !is_final && bytes.len() <= MAX_ENTITY_LEN {
+                return Err(MarkDownError::Incomplete(Needed::Size(1)));
+            }
+
             let start = input.start();
             let span = Span::Range(start..start + cmp::min(100, input.len()));
 
@@ -58,11 +93,163 @@ where
             ));
         }
 
-        Ok(Self(content))
+        Ok(Self::Named(content))
+    }
+
+    fn parse_decimal(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        let mut start = input.clone();
+
+        // skip `&#`
+        input.split_to(2);
+
+        let digits = take_ascii_while(input, MAX_DECIMAL_DIGITS, |b| b.is_ascii_digit());
+        let terminator = input.as_str().as_bytes().first().copied();
+
+        if terminator != Some(b';') {
+            // No byte is present yet to rule the reference out; it's simply
+            // unterminated so far.
+            if !is_final && terminator.is_none() {
+                return Err(MarkDownError::Incomplete(Needed::Size(1)));
+            }
+
+            return Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                start.to_span(),
+            ));
+        }
+
+        if digits.is_empty() {
+            return Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                start.to_span(),
+            ));
+        }
+
+        input.split_to(1);
+
+        let raw = start.split_to(2 + digits.len() + 1);
+        let value = resolve_codepoint(digits.parse().unwrap_or(0));
+
+        Ok(Self::Decimal { raw, value })
+    }
+
+    fn parse_hex(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        let mut start = input.clone();
+
+        // skip `&#x` / `&#X`
+        input.split_to(3);
+
+        let digits = take_ascii_while(input, MAX_HEX_DIGITS, |b| b.is_ascii_hexdigit());
+        let terminator = input.as_str().as_bytes().first().copied();
+
+        if terminator != Some(b';') {
+            if !is_final && terminator.is_none() {
+                return Err(MarkDownError::Incomplete(Needed::Size(1)));
+            }
+
+            return Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                start.to_span(),
+            ));
+        }
+
+        if digits.is_empty() {
+            return Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                start.to_span(),
+            ));
+        }
+
+        input.split_to(1);
+
+        let raw = start.split_to(3 + digits.len() + 1);
+        let value = resolve_codepoint(u32::from_str_radix(&digits, 16).unwrap_or(0));
+
+        Ok(Self::Hex { raw, value })
+    }
+
+    fn parse_dispatch(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        let bytes = input.as_str().as_bytes();
+
+        if bytes.get(1) == Some(&b'#') {
+            if matches!(bytes.get(2), Some(b'x') | Some(b'X')) {
+                return Self::parse_hex(input, is_final);
+            }
+
+            return Self::parse_decimal(input, is_final);
+        }
+
+        Self::parse_named(input, is_final)
+    }
+
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of [`ControlFlow::Fatal`] when the unterminated reference
+    /// could still be completed by more bytes — no `;` has arrived yet, but
+    /// nothing seen so far rules the reference out either. `is_final`
+    /// mirrors [`Partial::is_final`]: pass `false` while more chunks may
+    /// still arrive, `true` once the caller has reached true EOF (at which
+    /// point a still-unterminated reference is reported the same way
+    /// [`Syntax::parse`] would).
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        next('&')
+            .parse(&mut input.clone())
+            .map_err(|err| MarkDownError::Kind(Kind::Entity, err.control_flow(), err.span()))?;
+
+        Self::parse_dispatch(input, is_final)
+    }
+}
+
+/// Consume up to `max` ASCII bytes matching `pred`, returning them as an owned `String`.
+fn take_ascii_while<I>(input: &mut I, max: usize, pred: impl Fn(u8) -> bool) -> String
+where
+    I: MarkDownInput,
+{
+    let len = input
+        .as_str()
+        .as_bytes()
+        .iter()
+        .take(max)
+        .take_while(|&&b| pred(b))
+        .count();
+
+    input.split_to(len).as_str().to_owned()
+}
+
+impl<I> Syntax<I> for Entity<I>
+where
+    I: MarkDownInput,
+{
+    fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
+        next('&')
+            .parse(&mut input.clone())
+            .map_err(|err| MarkDownError::Kind(Kind::Entity, err.control_flow(), err.span()))?;
+
+        Self::parse_dispatch(input, true)
     }
 
     fn to_span(&self) -> parserc::Span {
-        self.0.to_span()
+        match self {
+            Entity::Named(content) => content.to_span(),
+            Entity::Decimal { raw, .. } => raw.to_span(),
+            Entity::Hex { raw, .. } => raw.to_span(),
+        }
+    }
+}
+
+impl<I> ToSource for Entity<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        match self {
+            Entity::Named(content) => content.to_source(out),
+            Entity::Decimal { raw, .. } => raw.to_source(out),
+            Entity::Hex { raw, .. } => raw.to_source(out),
+        }
     }
 }
 
@@ -70,13 +257,13 @@ where
 mod tests {
     use parserc::{ControlFlow, Span, syntax::InputSyntaxExt};
 
-    use crate::{Entity, Kind, MarkDownError, TokenStream, entity::MAX_ENTITY_LEN};
+    use crate::{Entity, Kind, MarkDownError, Needed, ToSource, TokenStream, entity::MAX_ENTITY_LEN};
 
     #[test]
     fn test_entities() {
         assert_eq!(
             TokenStream::from("&amp;").parse(),
-            Ok(Entity(TokenStream::from("&amp;")))
+            Ok(Entity::Named(TokenStream::from("&amp;")))
         );
 
         let input = format!("&{};", "a".repeat(MAX_ENTITY_LEN));
@@ -117,4 +304,151 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_decimal_reference() {
+        assert_eq!(
+            TokenStream::from("&#35;").parse(),
+            Ok(Entity::Decimal {
+                raw: TokenStream::from("&#35;"),
+                value: '#',
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("&#0;").parse(),
+            Ok(Entity::Decimal {
+                raw: TokenStream::from("&#0;"),
+                value: '\u{FFFD}',
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("&#55296;").parse(),
+            Ok(Entity::Decimal {
+                raw: TokenStream::from("&#55296;"),
+                value: '\u{FFFD}',
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("&#9999999;").parse(),
+            Ok(Entity::Decimal {
+                raw: TokenStream::from("&#9999999;"),
+                value: '\u{FFFD}',
+            })
+        );
+
+        // Zero digits isn't a reference the `;` can terminate, even though
+        // nothing else about the input is malformed.
+        assert_eq!(
+            TokenStream::from("&#;").parse::<Entity<_>>(),
+            Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                Span::Range(0..3)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_hex_reference() {
+        assert_eq!(
+            TokenStream::from("&#xABCD;").parse(),
+            Ok(Entity::Hex {
+                raw: TokenStream::from("&#xABCD;"),
+                value: '\u{ABCD}',
+            })
+        );
+
+        assert_eq!(
+            TokenStream::from("&#X22;").parse(),
+            Ok(Entity::Hex {
+                raw: TokenStream::from("&#X22;"),
+                value: '"',
+            })
+        );
+
+        // Zero hex digits isn't a reference the `;` can terminate.
+        assert_eq!(
+            TokenStream::from("&#x;").parse::<Entity<_>>(),
+            Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                Span::Range(0..4)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        // A chunk boundary cut right before `;` reports `Incomplete`, not
+        // `Fatal`, as long as the caller hasn't reached true EOF yet.
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from("&amp"), false),
+            Err(MarkDownError::Incomplete(Needed::Size(1)))
+        );
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from("&#35"), false),
+            Err(MarkDownError::Incomplete(Needed::Size(1)))
+        );
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from("&#x22"), false),
+            Err(MarkDownError::Incomplete(Needed::Size(1)))
+        );
+
+        // Once the caller has reached true EOF, the same unterminated input
+        // is reported the way `Syntax::parse` would report it.
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from("&amp"), true),
+            Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                Span::Range(0..4)
+            ))
+        );
+
+        // Once the length budget is exceeded with still no `;`, it's fatal
+        // regardless of mode - no amount of extra bytes fixes it.
+        let input = format!("&{};", "a".repeat(MAX_ENTITY_LEN));
+
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from(input.as_str()), false),
+            Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                Span::Range(0..100)
+            ))
+        );
+
+        // A byte that can never complete into the construct (e.g. a second
+        // `#`) is fatal immediately, chunk boundary or not.
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from("&#3#5;"), false),
+            Err(MarkDownError::Kind(
+                Kind::Entity,
+                ControlFlow::Fatal,
+                Span::Range(0..6)
+            ))
+        );
+
+        // Complete input still parses the same way under partial mode.
+        assert_eq!(
+            Entity::parse_partial(&mut TokenStream::from("&amp;"), false),
+            Ok(Entity::Named(TokenStream::from("&amp;")))
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in ["&amp;", "&#35;", "&#xABCD;"] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<Entity<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use parserc::{ControlFlow, ParseError, Span};
 
 /// Error kind for markdown document parsing.
@@ -27,6 +29,14 @@ pub enum Kind {
     IdentationNonblankChunk,
     #[error("identation blank chunk")]
     IdentationBlankChunk,
+    #[error("fenced code block")]
+    FencedCodeBlock,
+    #[error("container block")]
+    ContainerBlock,
+    #[error("inline attribute block")]
+    Attributes,
+    #[error("document")]
+    Document,
 }
 
 impl Kind {
@@ -36,6 +46,48 @@ impl Kind {
     }
 }
 
+/// How many more bytes a truncated parse needs before it can be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Needed {
+    /// At least this many more bytes; there may turn out to be more still.
+    Size(usize),
+    /// More bytes are required, but how many can't be predicted up front.
+    Unknown,
+}
+
+/// A machine-applicable fix: replace `span` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Suggestion {
+    /// The span to replace.
+    pub span: Span,
+    /// The text to replace it with.
+    pub replacement: String,
+}
+
+/// A rich diagnostic, borrowing rustc's parser error shape: a primary span
+/// and message, an optional free-form help note, and an optional
+/// machine-applicable [`Suggestion`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    /// The span the error is anchored to.
+    pub primary: Span,
+    /// Human-readable explanation of what went wrong.
+    pub message: String,
+    /// An optional free-form note suggesting how to fix it.
+    pub help: Option<String>,
+    /// An optional machine-applicable fix.
+    pub suggestion: Option<Suggestion>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 /// Error kinds returns by `compiler`.
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
 pub enum MarkDownError {
@@ -43,6 +95,31 @@ pub enum MarkDownError {
     Other(parserc::Kind),
     #[error("{1:?}: Parsing `{0:?}` error, {1:?}")]
     Kind(Kind, ControlFlow, Span),
+    /// The input ended before a construct could be proven complete or
+    /// malformed — e.g. an entity reference with no `;` yet, or a fenced
+    /// code block with no closing fence yet. Only ever returned by a type's
+    /// opt-in `parse_partial`; `Syntax::parse` never returns it. Streaming
+    /// callers should buffer the unconsumed tail, append the next chunk, and
+    /// retry.
+    #[error("incomplete input, needs more bytes: {0:?}")]
+    Incomplete(Needed),
+    /// A [`Kind`] error with an explanation, and possibly a fix, attached.
+    /// Always [`ControlFlow::Recovable`]: raised where a plain
+    /// [`MarkDownError::Kind`] would otherwise be, never by a combinator
+    /// that has no richer explanation to offer.
+    #[error("{0}")]
+    Diagnostic(Diagnostic),
+}
+
+impl MarkDownError {
+    /// If this is [`MarkDownError::Incomplete`], how many more bytes the
+    /// caller should feed before retrying.
+    pub fn needed(&self) -> Option<Needed> {
+        match self {
+            MarkDownError::Incomplete(needed) => Some(*needed),
+            _ => None,
+        }
+    }
 }
 
 impl From<parserc::Kind> for MarkDownError {
@@ -61,6 +138,11 @@ impl ParseError for MarkDownError {
         match self {
             MarkDownError::Other(kind) => kind.control_flow(),
             MarkDownError::Kind(_, control_flow, _) => *control_flow,
+            // Not actually fatal, but not a `Recovable` alternative to fall
+            // through to either: the caller, not another combinator, is the
+            // one who can resolve this by feeding more bytes.
+            MarkDownError::Incomplete(_) => ControlFlow::Fatal,
+            MarkDownError::Diagnostic(_) => ControlFlow::Recovable,
         }
     }
 
@@ -70,6 +152,8 @@ impl ParseError for MarkDownError {
             MarkDownError::Kind(kind, _, span) => {
                 MarkDownError::Kind(kind, ControlFlow::Fatal, span)
             }
+            MarkDownError::Incomplete(needed) => MarkDownError::Incomplete(needed),
+            MarkDownError::Diagnostic(diagnostic) => MarkDownError::Diagnostic(diagnostic),
         }
     }
 
@@ -77,6 +161,8 @@ impl ParseError for MarkDownError {
         match self {
             MarkDownError::Other(kind) => kind.span(),
             MarkDownError::Kind(_, _, span) => span.clone(),
+            MarkDownError::Incomplete(_) => Span::None,
+            MarkDownError::Diagnostic(diagnostic) => diagnostic.primary.clone(),
         }
     }
 }
@@ -1,6 +1,6 @@
 use parserc::{ControlFlow, Parser, next, syntax::Syntax};
 
-use crate::{Kind, LineEnding, MarkDownError, MarkDownInput};
+use crate::{Kind, LineEnding, MarkDownError, MarkDownInput, ToSource};
 
 /// Escaped characters
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -38,36 +38,39 @@ where
 
         next('\\').parse(input)?;
 
-        match input.iter().next() {
-            Some('*') => {
+        // Every marker this dispatch cares about is ASCII (`< 0x80`), and every
+        // continuation byte of a wider UTF-8 sequence is `>= 0x80`, so matching on
+        // the leading byte can never land mid-character.
+        match input.as_str().as_bytes().first() {
+            Some(b'*') => {
                 input.split_to(1);
                 Ok(Escaped::Star(start.split_to(2)))
             }
-            Some('<') => {
+            Some(b'<') => {
                 input.split_to(1);
                 Ok(Escaped::Lt(start.split_to(2)))
             }
-            Some('[') => {
+            Some(b'[') => {
                 input.split_to(1);
                 Ok(Escaped::Square(start.split_to(2)))
             }
-            Some('`') => {
+            Some(b'`') => {
                 input.split_to(1);
                 Ok(Escaped::Backtick(start.split_to(2)))
             }
-            Some('.') => {
+            Some(b'.') => {
                 input.split_to(1);
                 Ok(Escaped::Dot(start.split_to(2)))
             }
-            Some('#') => {
+            Some(b'#') => {
                 input.split_to(1);
                 Ok(Escaped::Pound(start.split_to(2)))
             }
-            Some('&') => {
+            Some(b'&') => {
                 input.split_to(1);
                 Ok(Escaped::And(start.split_to(2)))
             }
-            Some('\\') => {
+            Some(b'\\') => {
                 input.split_to(1);
                 Ok(Escaped::Backslash(start.split_to(2)))
             }
@@ -105,11 +108,30 @@ where
     }
 }
 
+impl<I> ToSource for Escaped<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        match self {
+            Escaped::Star(input) => input.to_source(out),
+            Escaped::Lt(input) => input.to_source(out),
+            Escaped::Square(input) => input.to_source(out),
+            Escaped::Backtick(input) => input.to_source(out),
+            Escaped::Dot(input) => input.to_source(out),
+            Escaped::Pound(input) => input.to_source(out),
+            Escaped::And(input) => input.to_source(out),
+            Escaped::Backslash(input) => input.to_source(out),
+            Escaped::HardlineBreak(input) => input.to_source(out),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use parserc::syntax::InputSyntaxExt;
 
-    use crate::{Escaped, TokenStream};
+    use crate::{Escaped, ToSource, TokenStream};
 
     #[test]
     fn test_escaped() {
@@ -163,4 +185,17 @@ mod tests {
             Ok(Escaped::HardlineBreak(TokenStream::from("\\\r\n")))
         );
     }
+
+    #[test]
+    fn test_to_source() {
+        for raw in [r#"\*"#, r#"\<"#, r#"\["#, r#"\`"#, r#"\."#, r#"\#"#, r#"\&"#, r#"\\"#, "\\\n", "\\\r\n"] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<Escaped<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
 }
@@ -1,10 +1,12 @@
 use parserc::{
     ControlFlow, Parser,
-    syntax::{InputSyntaxExt, Limits, Syntax, token},
+    syntax::{InputSyntaxExt, Syntax},
     take_while,
 };
 
-use crate::{IndentationTo, Kind, LineEnding, MarkDownError, MarkDownInput};
+use crate::{
+    Attributes, IndentationTo, Kind, LineEnding, MarkDownError, MarkDownInput, Needed, ToSource,
+};
 
 /// An [`ATX heading`] parser.
 ///
@@ -23,6 +25,8 @@ where
     pub seperate: I,
     /// heading content.
     pub content: I,
+    /// Optional Djot/Pandoc-style `{#id .class key=value}` block trailing the content.
+    pub attributes: Option<Attributes<I>>,
     /// Optional line ending chars.
     pub line_ending: Option<LineEnding<I>>,
 }
@@ -36,10 +40,24 @@ where
         let ident_whitespaces =
             IndentationTo::<I, 3>::parse(input).map_err(Kind::ATXHeading.map())?;
 
-        token!(Pounds, |c: char| c == '#');
+        // `#` is a single ASCII byte, so counting the run by byte avoids a
+        // UTF-8 decode per character on this hot path.
+        let pounds_len = input
+            .as_str()
+            .as_bytes()
+            .iter()
+            .take_while(|&&b| b == b'#')
+            .count();
+
+        if pounds_len < 1 || pounds_len > 6 {
+            return Err(MarkDownError::Kind(
+                Kind::ATXHeading,
+                ControlFlow::Recovable,
+                input.to_span(),
+            ));
+        }
 
-        let leading_pounds =
-            Limits::<Pounds<_>, 1, 7>::parse(input).map_err(Kind::ATXHeading.map())?;
+        let leading_pounds = input.split_to(pounds_len);
 
         let mut content = take_while(|c: char| c != '\r' && c != '\n').parse(input)?;
 
@@ -55,10 +73,13 @@ where
             ));
         }
 
+        let (content, attributes) = Attributes::split_trailing(content);
+
         Ok(Self {
             ident_whitespaces,
-            leading_pounds: leading_pounds.0.0,
+            leading_pounds,
             content,
+            attributes,
             line_ending,
             seperate,
         })
@@ -70,15 +91,65 @@ where
             .to_span()
             .union(&self.leading_pounds.to_span())
             .union(&self.content.to_span())
+            .union(&self.attributes.to_span())
             .union(&self.line_ending.to_span())
     }
 }
 
+impl<I> ToSource for ATXHeading<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.ident_whitespaces.to_source(out);
+        self.leading_pounds.to_source(out);
+        self.seperate.to_source(out);
+        self.content.to_source(out);
+        self.attributes.to_source(out);
+        self.line_ending.to_source(out);
+    }
+}
+
+impl<I> ATXHeading<I>
+where
+    I: MarkDownInput,
+{
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of committing to a result that a later chunk could still
+    /// change: a heading with no line ending yet whose content ran all the
+    /// way to the end of the buffer (more content, or a trailing attribute
+    /// block, could still follow), or the same "no separator, no line
+    /// ending" situation that would otherwise be
+    /// [`ControlFlow::Recovable`].
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        if !is_final {
+            let mut probe = input.clone();
+
+            match Self::parse(&mut probe) {
+                Ok(heading) if heading.line_ending.is_none() && probe.is_empty() => {
+                    return Err(MarkDownError::Incomplete(Needed::Unknown));
+                }
+                Err(MarkDownError::Kind(Kind::ATXHeading, ControlFlow::Recovable, _))
+                    if probe.is_empty() =>
+                {
+                    return Err(MarkDownError::Incomplete(Needed::Unknown));
+                }
+                _ => {}
+            }
+        }
+
+        Self::parse(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use parserc::{ControlFlow, Span, syntax::InputSyntaxExt};
 
-    use crate::{ATXHeading, IndentationTo, Kind, LineEnding, MarkDownError, TokenStream};
+    use crate::{
+        ATXHeading, Attributes, IndentationTo, Kind, LineEnding, MarkDownError, Needed, ToSource,
+        TokenStream,
+    };
 
     #[test]
     fn test_atx_heading() {
@@ -89,6 +160,7 @@ mod tests {
                 leading_pounds: TokenStream::from((1, "######")),
                 seperate: TokenStream::from((7, " ")),
                 content: TokenStream::from((8, "hello world")),
+                attributes: None,
                 line_ending: Some(LineEnding::CrLf(TokenStream::from((19, "\r\n"))))
             })
         );
@@ -100,6 +172,7 @@ mod tests {
                 leading_pounds: TokenStream::from("######"),
                 seperate: TokenStream::from((6, " ")),
                 content: TokenStream::from((7, "hello world ")),
+                attributes: None,
                 line_ending: None
             })
         );
@@ -111,10 +184,28 @@ mod tests {
                 leading_pounds: TokenStream::from((3, "#")),
                 seperate: TokenStream::from((4, " ")),
                 content: TokenStream::from((5, "")),
+                attributes: None,
                 line_ending: None
             })
         );
 
+        assert_eq!(
+            TokenStream::from("###### hello {#intro .big}\r\n").parse(),
+            Ok(ATXHeading {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                leading_pounds: TokenStream::from("######"),
+                seperate: TokenStream::from((6, " ")),
+                content: TokenStream::from((7, "hello ")),
+                attributes: Some(Attributes {
+                    id: Some(TokenStream::from((15, "intro"))),
+                    classes: vec![TokenStream::from((22, "big"))],
+                    pairs: vec![],
+                    raw: TokenStream::from((13, "{#intro .big}")),
+                }),
+                line_ending: Some(LineEnding::CrLf(TokenStream::from((26, "\r\n"))))
+            })
+        );
+
         assert_eq!(
             TokenStream::from("   #").parse::<ATXHeading<_>>(),
             Err(MarkDownError::Kind(
@@ -124,4 +215,58 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_atx_heading_parse_partial() {
+        // No line ending yet, content ran to the end of the buffer: more
+        // content (or a trailing attribute block) could still arrive.
+        assert_eq!(
+            ATXHeading::parse_partial(&mut TokenStream::from("###### hello world"), false),
+            Err(MarkDownError::Incomplete(Needed::Unknown))
+        );
+
+        // At true EOF, the same buffer resolves the way `Syntax::parse` would.
+        assert_eq!(
+            ATXHeading::parse_partial(&mut TokenStream::from("###### hello world"), true),
+            Ok(ATXHeading {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                leading_pounds: TokenStream::from("######"),
+                seperate: TokenStream::from((6, " ")),
+                content: TokenStream::from((7, "hello world")),
+                attributes: None,
+                line_ending: None
+            })
+        );
+
+        // A line ending already present resolves the same way either mode.
+        assert_eq!(
+            ATXHeading::parse_partial(&mut TokenStream::from("###### hello world\n"), false),
+            Ok(ATXHeading {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                leading_pounds: TokenStream::from("######"),
+                seperate: TokenStream::from((6, " ")),
+                content: TokenStream::from((7, "hello world")),
+                attributes: None,
+                line_ending: Some(LineEnding::LF(TokenStream::from((18, "\n"))))
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in [
+            " ###### hello world\r\n",
+            "###### hello world ",
+            "   # ",
+            "###### hello {#intro .big}\r\n",
+        ] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<ATXHeading<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
 }
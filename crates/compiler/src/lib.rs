@@ -26,3 +26,24 @@ pub use header::*;
 
 mod code;
 pub use code::*;
+
+mod container;
+pub use container::*;
+
+mod attributes;
+pub use attributes::*;
+
+mod arena;
+pub use arena::*;
+
+mod document;
+pub use document::*;
+
+mod source_map;
+pub use source_map::*;
+
+mod partial;
+pub use partial::*;
+
+mod to_source;
+pub use to_source::*;
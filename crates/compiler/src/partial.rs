@@ -0,0 +1,52 @@
+use crate::MarkDownInput;
+
+/// A chunk of buffered input, paired with whether more chunks may still
+/// follow.
+///
+/// This is a thin bookkeeping wrapper for streaming callers driving the
+/// `parse_partial(input, is_final)` family of associated functions (see
+/// [`crate::Entity::parse_partial`], [`crate::FencedCodeBlock::parse_partial`],
+/// and friends) - it does **not** implement [`MarkDownInput`] itself. Each
+/// `parse_partial` takes the inner input directly; `Partial` only tracks the
+/// `is_final` flag alongside the buffer so a caller doesn't have to thread it
+/// through by hand.
+///
+/// Typical use: keep appending newly-received bytes to `input`, calling
+/// `parse_partial(&mut partial.input, partial.is_final())` after each append;
+/// on [`crate::MarkDownError::Incomplete`], wait for more bytes (or, once
+/// there genuinely are no more, call [`Partial::set_final`] and retry so the
+/// construct resolves one way or the other).
+#[derive(Debug, Clone)]
+pub struct Partial<I>
+where
+    I: MarkDownInput,
+{
+    /// The buffered input seen so far.
+    pub input: I,
+    /// Whether `input` is known to be the last chunk - no more bytes will
+    /// ever arrive.
+    is_final: bool,
+}
+
+impl<I> Partial<I>
+where
+    I: MarkDownInput,
+{
+    /// Wraps `input`, initially expecting more chunks to follow.
+    pub fn new(input: I) -> Self {
+        Self {
+            input,
+            is_final: false,
+        }
+    }
+
+    /// Whether `input` is known to be the last chunk.
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    /// Marks that no more chunks will follow: `input` is all there is.
+    pub fn set_final(&mut self) {
+        self.is_final = true;
+    }
+}
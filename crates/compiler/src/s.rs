@@ -1,10 +1,41 @@
 use parserc::{
     ControlFlow, ParseError, Parser,
     syntax::{LimitsFrom, LimitsTo, Syntax, keyword},
-    take_while,
 };
 
-use crate::{Kind, MarkDownError, MarkDownInput};
+use crate::{Kind, MarkDownError, MarkDownInput, Needed, ToSource};
+
+/// Scans the longest leading run of `src` that `S`/`S1` accept: whitespace
+/// other than `\n`/`\r`. ASCII whitespace is scanned byte-by-byte without
+/// decoding a `char`; a multibyte leading byte falls back to decoding one
+/// `char` so non-ASCII whitespace (e.g. U+00A0) is still recognized, and the
+/// loop resumes in byte mode right after it.
+fn scan_whitespace_run(src: &str) -> usize {
+    let bytes = src.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' | b'\r' => break,
+            b' ' | b'\t' | 0x0b | 0x0c => i += 1,
+            b if b < 0x80 => break,
+            _ => {
+                let ch = src[i..]
+                    .chars()
+                    .next()
+                    .expect("Safety: `i` always sits on a UTF-8 char boundary");
+
+                if !ch.is_whitespace() {
+                    break;
+                }
+
+                i += ch.len_utf8();
+            }
+        }
+    }
+
+    i
+}
 
 /// Whitespace chars.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -18,9 +49,8 @@ where
     I: MarkDownInput,
 {
     fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
-        take_while(|c: char| c != '\n' && c != '\r' && c.is_whitespace())
-            .parse(input)
-            .map(|c| Self(c))
+        let len = scan_whitespace_run(input.as_str());
+        Ok(Self(input.split_to(len)))
     }
 
     fn to_span(&self) -> parserc::Span {
@@ -28,6 +58,15 @@ where
     }
 }
 
+impl<I> ToSource for S<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
 /// Non-empty whitespace chars.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -40,8 +79,8 @@ where
     I: MarkDownInput,
 {
     fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
-        let content =
-            take_while(|c: char| c != '\n' && c != '\r' && c.is_whitespace()).parse(input)?;
+        let len = scan_whitespace_run(input.as_str());
+        let content = input.split_to(len);
 
         if content.is_empty() {
             return Err(MarkDownError::Kind(
@@ -59,6 +98,15 @@ where
     }
 }
 
+impl<I> ToSource for S1<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
 /// Up to `N` spaces of indentation.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -85,6 +133,39 @@ where
     }
 }
 
+impl<I, const N: usize> IndentationTo<I, N>
+where
+    I: MarkDownInput,
+{
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of a short result when fewer than `N` spaces were captured and
+    /// the buffer ran dry - more bytes could still push the count up to (but
+    /// never past) `N`. Once the cap `N` itself is reached, the value is
+    /// already fixed regardless of what follows, so it's never ambiguous.
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        if !is_final {
+            let mut probe = input.clone();
+
+            if let Ok(result) = Self::parse(&mut probe) {
+                if result.0.len() < N && probe.is_empty() {
+                    return Err(MarkDownError::Incomplete(Needed::Unknown));
+                }
+            }
+        }
+
+        Self::parse(input)
+    }
+}
+
+impl<I, const N: usize> ToSource for IndentationTo<I, N>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
 /// Preceded by `N` or more spaces of indentation
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -111,6 +192,36 @@ where
     }
 }
 
+impl<I, const N: usize> IndentationFrom<I, N>
+where
+    I: MarkDownInput,
+{
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of a fixed result whenever the buffer ran dry right after a
+    /// successful parse - unlike [`IndentationTo`] there's no upper cap, so
+    /// the captured run could still grow with the next chunk.
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        if !is_final {
+            let mut probe = input.clone();
+
+            if Self::parse(&mut probe).is_ok() && probe.is_empty() {
+                return Err(MarkDownError::Incomplete(Needed::Unknown));
+            }
+        }
+
+        Self::parse(input)
+    }
+}
+
+impl<I, const N: usize> ToSource for IndentationFrom<I, N>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
 /// Line ending characters.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -161,6 +272,39 @@ where
     }
 }
 
+impl<I> LineEnding<I>
+where
+    I: MarkDownInput,
+{
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of [`ControlFlow::Recovable`]/[`ControlFlow::Fatal`] when the
+    /// buffer ran out right where a line ending could still be starting - an
+    /// empty buffer, or a lone `\r` that might still be followed by `\n`.
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        if !is_final {
+            let remaining = input.as_str();
+
+            if remaining.is_empty() || remaining == "\r" {
+                return Err(MarkDownError::Incomplete(Needed::Size(1)));
+            }
+        }
+
+        Self::parse(input)
+    }
+}
+
+impl<I> ToSource for LineEnding<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        match self {
+            LineEnding::LF(input) => input.to_source(out),
+            LineEnding::CrLf(input) => input.to_source(out),
+        }
+    }
+}
+
 /// A blank line contains no characters other than the line ending characters.
 ///
 /// See [`https://spec.commonmark.org/0.31.2/#blank-lines`]
@@ -186,3 +330,115 @@ where
         self.0.to_span()
     }
 }
+
+impl<I> BlankLine<I>
+where
+    I: MarkDownInput,
+{
+    /// Like [`Syntax::parse`], but passes [`MarkDownError::Incomplete`]
+    /// through from [`LineEnding::parse_partial`] unchanged, rather than
+    /// remapping it to [`Kind::BlankLine`] the way a resolved error would be -
+    /// remapping would destroy the `Incomplete` signal a streaming caller
+    /// needs.
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        match LineEnding::parse_partial(input, is_final) {
+            Ok(content) => Ok(Self(content.into_input())),
+            Err(err @ MarkDownError::Incomplete(_)) => Err(err),
+            Err(err) => Err(Kind::BlankLine.map()(err)),
+        }
+    }
+}
+
+impl<I> ToSource for BlankLine<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.0.to_source(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parserc::syntax::InputSyntaxExt;
+
+    use crate::{
+        BlankLine, IndentationFrom, IndentationTo, LineEnding, MarkDownError, Needed, TokenStream,
+    };
+
+    #[test]
+    fn test_line_ending_parse_partial() {
+        // An empty buffer, or a lone `\r`, might still turn into a line
+        // ending once more bytes arrive.
+        assert_eq!(
+            LineEnding::parse_partial(&mut TokenStream::from(""), false),
+            Err(MarkDownError::Incomplete(Needed::Size(1)))
+        );
+        assert_eq!(
+            LineEnding::parse_partial(&mut TokenStream::from("\r"), false),
+            Err(MarkDownError::Incomplete(Needed::Size(1)))
+        );
+
+        // At true EOF, the same buffer is reported the way `Syntax::parse`
+        // would report it.
+        assert!(LineEnding::parse_partial(&mut TokenStream::from("\r"), true).is_err());
+
+        // A resolved line ending parses identically either way.
+        assert_eq!(
+            LineEnding::parse_partial(&mut TokenStream::from("\r\n"), false),
+            Ok(LineEnding::CrLf(TokenStream::from("\r\n")))
+        );
+    }
+
+    #[test]
+    fn test_blank_line_parse_partial() {
+        assert_eq!(
+            BlankLine::parse_partial(&mut TokenStream::from(""), false),
+            Err(MarkDownError::Incomplete(Needed::Size(1)))
+        );
+
+        assert_eq!(
+            BlankLine::parse_partial(&mut TokenStream::from("\n"), false),
+            Ok(BlankLine(TokenStream::from("\n")))
+        );
+    }
+
+    #[test]
+    fn test_indentation_to_parse_partial() {
+        // Fewer than `N` spaces captured, buffer ran dry: the count could
+        // still grow.
+        assert_eq!(
+            IndentationTo::<_, 3>::parse_partial(&mut TokenStream::from("  "), false),
+            Err(MarkDownError::Incomplete(Needed::Unknown))
+        );
+
+        // The cap itself was reached: the value is fixed regardless of what
+        // follows.
+        assert_eq!(
+            IndentationTo::<_, 3>::parse_partial(&mut TokenStream::from("   "), false),
+            Ok(IndentationTo(TokenStream::from("   ")))
+        );
+
+        // Followed by a non-space char: also fixed.
+        assert_eq!(
+            IndentationTo::<_, 3>::parse_partial(&mut TokenStream::from("  x"), false),
+            Ok(IndentationTo(TokenStream::from("  ")))
+        );
+    }
+
+    #[test]
+    fn test_indentation_from_parse_partial() {
+        // Buffer ran dry right after a successful parse: more spaces could
+        // still follow.
+        assert_eq!(
+            IndentationFrom::<_, 2>::parse_partial(&mut TokenStream::from("   "), false),
+            Err(MarkDownError::Incomplete(Needed::Unknown))
+        );
+
+        // Followed by a non-space char: fixed.
+        assert_eq!(
+            IndentationFrom::<_, 2>::parse_partial(&mut TokenStream::from("   x"), false),
+            Ok(IndentationFrom(TokenStream::from("   ")))
+        );
+    }
+}
@@ -0,0 +1,440 @@
+use parserc::{ParseError, Span};
+
+use crate::{Diagnostic, MarkDownError};
+
+/// A 1-based line / 0-based column position, with the column counted in
+/// characters (not bytes), as returned by [`SourceMap::location`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineColumn {
+    /// 1-based line number.
+    pub line: usize,
+    /// 0-based column number, in characters.
+    pub column: usize,
+}
+
+/// A span resolved all the way down to a named file and a 1-based line, as
+/// returned by [`SourceMap::resolve`].
+///
+/// `col_start` is counted on `line`; `col_end` is counted on the line the
+/// span ends on, which for a span that crosses a line ending is a later
+/// line than `line`. Neither is a byte count.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// The name the span's file was registered under via [`SourceMap::add_file`].
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    /// 0-based column the span starts at, in characters.
+    pub col_start: usize,
+    /// 0-based column the span ends at, in characters.
+    pub col_end: usize,
+}
+
+/// One registered file: its name, the disjoint byte-offset window it was
+/// assigned, and its precomputed line starts.
+#[derive(Debug, Clone)]
+struct FileEntry {
+    name: String,
+    base_offset: usize,
+    source: String,
+    /// Byte offset of the first character of each line, relative to this
+    /// file's own `source`; always starts with `0`.
+    line_starts: Vec<usize>,
+}
+
+impl FileEntry {
+    fn new(name: String, base_offset: usize, source: String) -> Self {
+        let mut line_starts = vec![0];
+
+        line_starts.extend(
+            source
+                .bytes()
+                .enumerate()
+                .filter(|(_, b)| *b == b'\n')
+                .map(|(index, _)| index + 1),
+        );
+
+        Self {
+            name,
+            base_offset,
+            source,
+            line_starts,
+        }
+    }
+
+    fn location(&self, local_offset: usize) -> LineColumn {
+        let local_offset = local_offset.min(self.source.len());
+
+        let line = self.line_starts.partition_point(|&start| start <= local_offset);
+        let line_start = self.line_starts[line - 1];
+
+        let column = self.source[line_start..local_offset].chars().count();
+
+        LineColumn { line, column }
+    }
+
+    /// This line's text, without its trailing line ending.
+    fn line_text(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = self
+            .line_starts
+            .get(line)
+            .copied()
+            .unwrap_or(self.source.len());
+
+        self.source[start..end].trim_end_matches(['\n', '\r'])
+    }
+}
+
+/// Resolves the byte offsets carried by a [`Span`] back to line/column
+/// positions in the original source, for surfacing [`MarkDownError`]s to a
+/// CLI or editor.
+///
+/// A single `SourceMap` can back more than one input: [`SourceMap::add_file`]
+/// assigns each file a disjoint window of the byte-offset space, the same
+/// way a compiler front end's source map does, so spans from any registered
+/// file resolve through the same `SourceMap`. Line starts are scanned up
+/// front per file, and lookups binary-search them, so repeated resolution
+/// (one per diagnostic) stays cheap even on large documents.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    files: Vec<FileEntry>,
+}
+
+impl Default for SourceMap {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl SourceMap {
+    /// Builds a `SourceMap` over a single, unnamed `source`.
+    ///
+    /// Equivalent to [`SourceMap::empty`] followed by one [`SourceMap::add_file`].
+    pub fn new(source: impl Into<String>) -> Self {
+        let mut map = Self::empty();
+        map.add_file("<input>", source);
+        map
+    }
+
+    /// Builds a `SourceMap` with no files registered yet.
+    pub fn empty() -> Self {
+        Self { files: vec![] }
+    }
+
+    /// Registers `source` under `name`, assigning it the next disjoint
+    /// byte-offset window, and returns that window's starting offset - the
+    /// amount callers must add to a byte offset local to `source` to turn it
+    /// into the global offset this `SourceMap` expects.
+    pub fn add_file(&mut self, name: impl Into<String>, source: impl Into<String>) -> usize {
+        let source = source.into();
+
+        let base_offset = self
+            .files
+            .last()
+            .map(|file| file.base_offset + file.source.len())
+            .unwrap_or(0);
+
+        self.files
+            .push(FileEntry::new(name.into(), base_offset, source));
+
+        base_offset
+    }
+
+    /// The registered file whose window contains `offset`; the last file
+    /// registered if `offset` runs past every window's end.
+    fn file_at(&self, offset: usize) -> &FileEntry {
+        let index = self.files.partition_point(|file| file.base_offset <= offset);
+        &self.files[index.saturating_sub(1)]
+    }
+
+    /// Resolves a byte `offset` to a [`LineColumn`] within whichever
+    /// registered file's window contains it. Offsets past the end of that
+    /// file resolve to the position right after its last character.
+    pub fn location(&self, offset: usize) -> LineColumn {
+        let file = self.file_at(offset);
+        file.location(offset - file.base_offset)
+    }
+
+    /// Resolves a [`Span`]'s start and end byte offsets to their
+    /// [`LineColumn`] positions.
+    pub fn span_location(&self, span: &Span) -> (LineColumn, LineColumn) {
+        match span {
+            Span::Range(range) => (self.location(range.start), self.location(range.end)),
+            Span::RangeTo(range) => (self.location(0), self.location(range.end)),
+            _ => (self.location(0), self.location(0)),
+        }
+    }
+
+    /// Resolves a [`Span`] all the way down to a [`Location`]: the name of
+    /// the file it falls in, plus the line/column range within that file.
+    pub fn resolve(&self, span: &Span) -> Location {
+        let (start, end) = match span {
+            Span::Range(range) => (range.start, range.end),
+            Span::RangeTo(range) => (0, range.end),
+            _ => (0, 0),
+        };
+
+        let file = self.file_at(start);
+        let start_lc = file.location(start - file.base_offset);
+        let end_lc = file.location(end.saturating_sub(file.base_offset));
+
+        Location {
+            file: file.name.clone(),
+            line: start_lc.line,
+            col_start: start_lc.column,
+            col_end: end_lc.column,
+        }
+    }
+
+    /// Formats a [`Span`] as `line:col` for a zero-length span, or
+    /// `line:col..line:col` for a range; a span this map can't resolve to a
+    /// position falls back to `start..end` byte offsets.
+    pub fn format_span(&self, span: &Span) -> String {
+        let Span::Range(range) = span else {
+            return "0..0".to_string();
+        };
+
+        if range.start == range.end {
+            let pos = self.location(range.start);
+            return format!("{}:{}", pos.line, pos.column);
+        }
+
+        let (start, end) = self.span_location(span);
+
+        format!("{}:{}..{}:{}", start.line, start.column, end.line, end.column)
+    }
+
+    /// Formats `error`'s span the way [`SourceMap::format_span`] does.
+    pub fn format_error(&self, error: &MarkDownError) -> String {
+        self.format_span(&error.span())
+    }
+}
+
+impl MarkDownError {
+    /// Resolves this error's span to its start/end [`LineColumn`] positions
+    /// via `source_map`, without re-scanning the source.
+    pub fn line_column(&self, source_map: &SourceMap) -> (LineColumn, LineColumn) {
+        source_map.span_location(&self.span())
+    }
+
+    /// Renders this error as a `file:line:col: message` header followed by
+    /// the offending source line and a `^` caret underlining the span,
+    /// resolving through `source_map` the way a compiler diagnostic would.
+    pub fn pretty(&self, source_map: &SourceMap) -> String {
+        let location = source_map.resolve(&self.span());
+
+        let line_text = source_map
+            .files
+            .iter()
+            .find(|file| file.name == location.file)
+            .map(|file| file.line_text(location.line))
+            .unwrap_or("");
+
+        let caret_len = location
+            .col_end
+            .saturating_sub(location.col_start)
+            .max(1);
+
+        format!(
+            "{}:{}:{}: {}\n{}\n{}{}",
+            location.file,
+            location.line,
+            location.col_start,
+            self,
+            line_text,
+            " ".repeat(location.col_start),
+            "^".repeat(caret_len),
+        )
+    }
+}
+
+impl Diagnostic {
+    /// Renders this diagnostic the way [`MarkDownError::pretty`] renders a
+    /// plain [`Kind`] error - a `file:line:col: message` header, the
+    /// offending source line, and a `^` caret underlining [`Self::primary`]
+    /// - with any `help`/`suggestion` appended as trailing lines.
+    ///
+    /// [`Kind`]: crate::Kind
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let location = source_map.resolve(&self.primary);
+
+        let line_text = source_map
+            .files
+            .iter()
+            .find(|file| file.name == location.file)
+            .map(|file| file.line_text(location.line))
+            .unwrap_or("");
+
+        let caret_len = location
+            .col_end
+            .saturating_sub(location.col_start)
+            .max(1);
+
+        let mut out = format!(
+            "{}:{}:{}: {}\n{}\n{}{}",
+            location.file,
+            location.line,
+            location.col_start,
+            self.message,
+            line_text,
+            " ".repeat(location.col_start),
+            "^".repeat(caret_len),
+        );
+
+        if let Some(help) = &self.help {
+            out.push_str(&format!("\nhelp: {}", help));
+        }
+
+        if let Some(suggestion) = &self.suggestion {
+            out.push_str(&format!(
+                "\nsuggestion: replace with `{}`",
+                suggestion.replacement
+            ));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parserc::{ControlFlow, Span};
+
+    use crate::{Diagnostic, Kind, Location, LineColumn, MarkDownError, Needed, SourceMap, Suggestion};
+
+    #[test]
+    fn test_location() {
+        let map = SourceMap::new("line one\nline two\nline three");
+
+        assert_eq!(map.location(0), LineColumn { line: 1, column: 0 });
+        assert_eq!(map.location(4), LineColumn { line: 1, column: 4 });
+        assert_eq!(map.location(9), LineColumn { line: 2, column: 0 });
+        assert_eq!(map.location(18), LineColumn { line: 3, column: 0 });
+        assert_eq!(
+            map.location(1000),
+            LineColumn {
+                line: 3,
+                column: 10
+            }
+        );
+    }
+
+    #[test]
+    fn test_location_counts_characters_not_bytes() {
+        // `é` is 2 bytes in UTF-8, but a single character/column.
+        let map = SourceMap::new("café\nbar");
+
+        assert_eq!(
+            map.location("café".len()),
+            LineColumn { line: 1, column: 4 }
+        );
+        assert_eq!(
+            map.location("café\n".len()),
+            LineColumn { line: 2, column: 0 }
+        );
+    }
+
+    #[test]
+    fn test_format_span() {
+        let map = SourceMap::new("line one\nline two\n");
+
+        assert_eq!(map.format_span(&Span::Range(0..4)), "1:0..1:4");
+        assert_eq!(map.format_span(&Span::Range(9..9)), "2:0");
+        assert_eq!(map.format_span(&Span::None), "0..0");
+    }
+
+    #[test]
+    fn test_format_error() {
+        let map = SourceMap::new("line one\nline two\n");
+
+        let error = MarkDownError::Kind(Kind::Entity, ControlFlow::Fatal, Span::Range(9..13));
+
+        assert_eq!(map.format_error(&error), "2:0..2:4");
+
+        assert_eq!(
+            map.format_error(&MarkDownError::Incomplete(Needed::Size(1))),
+            "0..0"
+        );
+    }
+
+    #[test]
+    fn test_add_file_assigns_disjoint_windows() {
+        let mut map = SourceMap::empty();
+
+        let a_base = map.add_file("a.md", "one\ntwo\n");
+        let b_base = map.add_file("b.md", "three\nfour\n");
+
+        assert_eq!(a_base, 0);
+        assert_eq!(b_base, "one\ntwo\n".len());
+
+        assert_eq!(
+            map.resolve(&Span::Range(a_base + 4..a_base + 7)),
+            Location {
+                file: "a.md".to_string(),
+                line: 2,
+                col_start: 0,
+                col_end: 3,
+            }
+        );
+
+        assert_eq!(
+            map.resolve(&Span::Range(b_base..b_base + 5)),
+            Location {
+                file: "b.md".to_string(),
+                line: 1,
+                col_start: 0,
+                col_end: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        let map = SourceMap::new("# hello\n\n***\n");
+
+        let error = MarkDownError::Kind(Kind::Thematic, ControlFlow::Recovable, Span::Range(8..8));
+
+        assert_eq!(
+            error.pretty(&map),
+            format!("<input>:2:0: {}\n\n^", error)
+        );
+    }
+
+    #[test]
+    fn test_line_column() {
+        let map = SourceMap::new("# hello\n\n***\n");
+
+        let error = MarkDownError::Kind(Kind::Thematic, ControlFlow::Recovable, Span::Range(9..12));
+
+        assert_eq!(
+            error.line_column(&map),
+            (
+                LineColumn { line: 3, column: 0 },
+                LineColumn { line: 3, column: 3 }
+            )
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_render() {
+        let map = SourceMap::new("# hello\n\n-*\n");
+
+        let diagnostic = Diagnostic {
+            primary: Span::Range(9..10),
+            message: "thematic break requires at least three characters".to_string(),
+            help: None,
+            suggestion: Some(Suggestion {
+                span: Span::Range(9..11),
+                replacement: "---".to_string(),
+            }),
+        };
+
+        assert_eq!(
+            diagnostic.render(&map),
+            "<input>:3:0: thematic break requires at least three characters\n-*\n^\nsuggestion: replace with `---`"
+        );
+    }
+}
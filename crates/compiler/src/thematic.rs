@@ -1,9 +1,12 @@
 use parserc::{
-    ControlFlow, ParseError, Parser,
-    syntax::{InputSyntaxExt, Punctuated, Syntax, token},
+    ControlFlow, ParseError,
+    syntax::{InputSyntaxExt, Punctuated, Syntax},
 };
 
-use crate::{Identation, IndentationTo, Kind, LineEnding, MarkDownError, MarkDownInput};
+use crate::{
+    Diagnostic, IndentationTo, Kind, LineEnding, MarkDownError, MarkDownInput, Needed, S,
+    Suggestion, ToSource,
+};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -36,6 +39,40 @@ where
             ThematicChars::Minus(content) => content.len(),
         }
     }
+
+    /// The marker character this run repeats.
+    fn marker_char(&self) -> char {
+        match self {
+            ThematicChars::Stars(_) => '*',
+            ThematicChars::Underscores(_) => '_',
+            ThematicChars::Minus(_) => '-',
+        }
+    }
+}
+
+/// A mismatched marker character within an otherwise-consistent run.
+fn mismatched_marker(span: parserc::Span) -> MarkDownError {
+    MarkDownError::Diagnostic(Diagnostic {
+        primary: span,
+        message: "thematic break must use a single marker character (`*`, `-`, or `_`)"
+            .to_string(),
+        help: None,
+        suggestion: None,
+    })
+}
+
+/// A run that totals fewer than three marker characters, with a suggested
+/// fix that pads it out to three of `marker`.
+fn run_too_short(span: parserc::Span, marker: char) -> MarkDownError {
+    MarkDownError::Diagnostic(Diagnostic {
+        primary: span.clone(),
+        message: "thematic break requires at least three characters".to_string(),
+        help: None,
+        suggestion: Some(Suggestion {
+            span,
+            replacement: marker.to_string().repeat(3),
+        }),
+    })
 }
 
 impl<I> Syntax<I> for ThematicChars<I>
@@ -44,15 +81,30 @@ where
 {
     #[inline]
     fn parse(input: &mut I) -> Result<Self, <I as parserc::Input>::Error> {
-        token!(Stars, |c: char| c == '*');
-        token!(Underscores, |c: char| c == '_');
-        token!(Minus, |c: char| c == '-');
-
-        Stars::into_parser()
-            .map(|v| Self::Stars(v.0))
-            .or(Underscores::into_parser().map(|v| Self::Underscores(v.0)))
-            .or(Minus::into_parser().map(|v| Self::Minus(v.0)))
-            .parse(input)
+        // `*`, `_`, and `-` are all single-byte ASCII, so matching the
+        // leading byte and counting the run by byte avoids a UTF-8 decode
+        // per character on this hot path.
+        let bytes = input.as_str().as_bytes();
+
+        let marker = match bytes.first() {
+            Some(marker @ (b'*' | b'_' | b'-')) => *marker,
+            _ => {
+                return Err(MarkDownError::Kind(
+                    Kind::Thematic,
+                    ControlFlow::Recovable,
+                    input.to_span(),
+                ));
+            }
+        };
+
+        let run_len = bytes.iter().take_while(|&&b| b == marker).count();
+        let content = input.split_to(run_len);
+
+        Ok(match marker {
+            b'*' => Self::Stars(content),
+            b'_' => Self::Underscores(content),
+            _ => Self::Minus(content),
+        })
     }
 
     #[inline]
@@ -65,6 +117,19 @@ where
     }
 }
 
+impl<I> ToSource for ThematicChars<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        match self {
+            ThematicChars::Stars(content) => content.to_source(out),
+            ThematicChars::Underscores(content) => content.to_source(out),
+            ThematicChars::Minus(content) => content.to_source(out),
+        }
+    }
+}
+
 /// Leaf block: [`thematic breaks`]
 ///
 /// [`thematic breaks`]: https://spec.commonmark.org/0.31.2/#thematic-break
@@ -76,8 +141,9 @@ where
 {
     /// A line consisting of optionally up to three spaces of indentation
     pub ident_whitespaces: IndentationTo<I, 3>,
-    /// core thematic breaks chars.
-    pub breaks: Punctuated<ThematicChars<I>, Identation<I>>,
+    /// core thematic breaks chars, interspersed with any amount of
+    /// spaces/tabs between marker runs.
+    pub breaks: Punctuated<ThematicChars<I>, S<I>>,
     /// optional line end.
     pub line_ending: Option<LineEnding<I>>,
 }
@@ -92,16 +158,18 @@ where
         let breaks = Punctuated::<ThematicChars<I>, _>::parse(input)
             .map_err(|err| MarkDownError::Kind(Kind::Thematic, err.control_flow(), err.span()))?;
 
+        // A run directly followed by a different marker, with no spaces/tabs
+        // between them, ends `breaks` early; that next run is the mismatch.
+        if let Ok(next_run) = ThematicChars::<I>::parse(&mut input.clone()) {
+            return Err(mismatched_marker(next_run.to_span()));
+        }
+
         let id = breaks.pairs.first().map(|pair| pair.0.value());
 
         let Some(id) = id else {
             if let Some(tail) = &breaks.tail {
                 if tail.len() < 3 {
-                    return Err(MarkDownError::Kind(
-                        Kind::Thematic,
-                        ControlFlow::Recovable,
-                        tail.to_span(),
-                    ));
+                    return Err(run_too_short(tail.to_span(), tail.marker_char()));
                 }
 
                 let line_ending: Option<LineEnding<_>> = input.parse()?;
@@ -128,15 +196,12 @@ where
             ));
         };
 
+        let marker = breaks.pairs[0].0.marker_char();
         let mut len = 0;
 
         for (pair, _) in breaks.pairs.iter() {
             if pair.value() != id {
-                return Err(MarkDownError::Kind(
-                    Kind::Thematic,
-                    ControlFlow::Recovable,
-                    pair.to_span(),
-                ));
+                return Err(mismatched_marker(pair.to_span()));
             }
 
             len += pair.len();
@@ -144,22 +209,14 @@ where
 
         if let Some(tail) = &breaks.tail {
             if tail.value() != id {
-                return Err(MarkDownError::Kind(
-                    Kind::Thematic,
-                    ControlFlow::Recovable,
-                    tail.to_span(),
-                ));
+                return Err(mismatched_marker(tail.to_span()));
             }
 
             len += tail.len();
         }
 
         if len < 3 {
-            return Err(MarkDownError::Kind(
-                Kind::Thematic,
-                ControlFlow::Recovable,
-                breaks.to_span(),
-            ));
+            return Err(run_too_short(breaks.to_span(), marker));
         }
 
         let line_ending: Option<LineEnding<_>> = input.parse()?;
@@ -187,6 +244,56 @@ where
     }
 }
 
+impl<I> ToSource for ThematicBreaks<I>
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        self.ident_whitespaces.to_source(out);
+        self.breaks.to_source(out);
+        self.line_ending.to_source(out);
+    }
+}
+
+impl<I> ThematicBreaks<I>
+where
+    I: MarkDownInput,
+{
+    /// Like [`Syntax::parse`], but reports [`MarkDownError::Incomplete`]
+    /// instead of committing to a result that a later chunk could still
+    /// change: a run of break characters with no line ending yet that ran
+    /// all the way to the end of the buffer (the run, or its repeat count,
+    /// could still grow), or a [`ControlFlow::Recovable`] rejection of a
+    /// too-short run that ran out of buffer at exactly the same point.
+    pub fn parse_partial(input: &mut I, is_final: bool) -> Result<Self, <I as parserc::Input>::Error> {
+        if !is_final {
+            let mut probe = input.clone();
+
+            match Self::parse(&mut probe) {
+                Ok(breaks) if breaks.line_ending.is_none() && probe.is_empty() => {
+                    return Err(MarkDownError::Incomplete(Needed::Unknown));
+                }
+                Err(MarkDownError::Kind(Kind::Thematic, ControlFlow::Recovable, _))
+                    if probe.is_empty() =>
+                {
+                    return Err(MarkDownError::Incomplete(Needed::Unknown));
+                }
+                // A too-short run carries a suggestion; a mismatched-marker
+                // diagnostic doesn't, and a mismatch already found can't be
+                // un-found by more bytes.
+                Err(MarkDownError::Diagnostic(diagnostic))
+                    if diagnostic.suggestion.is_some() && probe.is_empty() =>
+                {
+                    return Err(MarkDownError::Incomplete(Needed::Unknown));
+                }
+                _ => {}
+            }
+        }
+
+        Self::parse(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use parserc::syntax::{InputSyntaxExt, Punctuated};
@@ -205,15 +312,15 @@ mod tests {
                     pairs: vec![
                         (
                             ThematicChars::Minus(TokenStream::from((3, "-"))),
-                            Identation(TokenStream::from((4, "   ")))
+                            S(TokenStream::from((4, "   ")))
                         ),
                         (
                             ThematicChars::Minus(TokenStream::from((7, "-"))),
-                            Identation(TokenStream::from((8, "  ")))
+                            S(TokenStream::from((8, "  ")))
                         ),
                         (
                             ThematicChars::Minus(TokenStream::from((10, "-"))),
-                            Identation(TokenStream::from((11, "   ")))
+                            S(TokenStream::from((11, "   ")))
                         )
                     ],
                     tail: None
@@ -230,11 +337,11 @@ mod tests {
                     pairs: vec![
                         (
                             ThematicChars::Minus(TokenStream::from((3, "-"))),
-                            Identation(TokenStream::from((4, "   ")))
+                            S(TokenStream::from((4, "   ")))
                         ),
                         (
                             ThematicChars::Minus(TokenStream::from((7, "-"))),
-                            Identation(TokenStream::from((8, "  ")))
+                            S(TokenStream::from((8, "  ")))
                         ),
                     ],
                     tail: Some(Box::new(ThematicChars::Minus(TokenStream::from((10, "-")))))
@@ -251,11 +358,11 @@ mod tests {
                     pairs: vec![
                         (
                             ThematicChars::Minus(TokenStream::from((3, "-"))),
-                            Identation(TokenStream::from((4, "   ")))
+                            S(TokenStream::from((4, "   ")))
                         ),
                         (
                             ThematicChars::Minus(TokenStream::from((7, "-"))),
-                            Identation(TokenStream::from((8, "  ")))
+                            S(TokenStream::from((8, "  ")))
                         ),
                     ],
                     tail: Some(Box::new(ThematicChars::Minus(TokenStream::from((10, "-")))))
@@ -264,4 +371,94 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_thematic_breaks_validation() {
+        use parserc::Span;
+
+        // Marker runs may be separated by any amount of spaces/tabs.
+        assert_eq!(
+            TokenStream::from("- - -").parse(),
+            Ok(ThematicBreaks {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                breaks: Punctuated {
+                    pairs: vec![
+                        (
+                            ThematicChars::Minus(TokenStream::from("-")),
+                            S(TokenStream::from((1, " ")))
+                        ),
+                        (
+                            ThematicChars::Minus(TokenStream::from((2, "-"))),
+                            S(TokenStream::from((3, " ")))
+                        ),
+                    ],
+                    tail: Some(Box::new(ThematicChars::Minus(TokenStream::from((4, "-")))))
+                },
+                line_ending: None,
+            })
+        );
+
+        // Mixing marker characters is rejected, with a diagnostic pinned to
+        // the offending run.
+        assert_eq!(
+            TokenStream::from("*-*").parse::<ThematicBreaks<_>>(),
+            Err(mismatched_marker(Span::Range(1..2)))
+        );
+
+        // Fewer than three marker characters total is rejected, with a
+        // suggestion padding the run out to three.
+        assert_eq!(
+            TokenStream::from("- -").parse::<ThematicBreaks<_>>(),
+            Err(run_too_short(Span::Range(0..3), '-'))
+        );
+    }
+
+    #[test]
+    fn test_thematic_breaks_parse_partial() {
+        // No line ending yet, run ran to the end of the buffer: the run
+        // could still grow with the next chunk.
+        assert_eq!(
+            ThematicBreaks::parse_partial(&mut TokenStream::from("---"), false),
+            Err(MarkDownError::Incomplete(Needed::Unknown))
+        );
+
+        // At true EOF, the same buffer resolves the way `Syntax::parse` would.
+        assert_eq!(
+            ThematicBreaks::parse_partial(&mut TokenStream::from("---"), true),
+            Ok(ThematicBreaks {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                breaks: Punctuated {
+                    pairs: vec![],
+                    tail: Some(Box::new(ThematicChars::Minus(TokenStream::from("---"))))
+                },
+                line_ending: None,
+            })
+        );
+
+        // A line ending already present resolves the same way either mode.
+        assert_eq!(
+            ThematicBreaks::parse_partial(&mut TokenStream::from("---\n"), false),
+            Ok(ThematicBreaks {
+                ident_whitespaces: IndentationTo(TokenStream::from("")),
+                breaks: Punctuated {
+                    pairs: vec![],
+                    tail: Some(Box::new(ThematicChars::Minus(TokenStream::from("---"))))
+                },
+                line_ending: Some(LineEnding::LF(TokenStream::from((3, "\n")))),
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_source() {
+        for raw in ["   -   -  -   ", "   -   -  -", "   -   -  -\n"] {
+            assert_eq!(
+                TokenStream::from(raw)
+                    .parse::<ThematicBreaks<_>>()
+                    .unwrap()
+                    .to_source_string(),
+                raw
+            );
+        }
+    }
 }
@@ -0,0 +1,85 @@
+use parserc::syntax::Punctuated;
+
+use crate::MarkDownInput;
+
+/// Lossless reconstruction of the exact source bytes a syntax tree was
+/// parsed from - the dual of [`parserc::syntax::Syntax`].
+///
+/// Every syntax type in this crate stores the input slices it matched
+/// rather than re-deriving them, so implementing `to_source` is always a
+/// concatenation of those stored slices in source order - the same order
+/// [`parserc::syntax::Syntax::to_span`] unions them in. For any `x` that
+/// parsed successfully, `to_source_string()` on the result equals the part
+/// of `x` the parse consumed byte-for-byte, including whitespace, trailing
+/// spaces, and CRLF vs LF, with two exceptions whose bytes are not kept and
+/// so are missing from the output: a [`crate::FencedCodeBlock`]'s closing
+/// fence indentation, the spaces/tabs after that fence, and its line ending;
+/// and whitespace after a trailing attribute block split off by
+/// [`crate::Attributes::split_trailing`].
+pub trait ToSource {
+    /// Appends this value's exact source text to `out`.
+    fn to_source(&self, out: &mut String);
+
+    /// Convenience wrapper around [`ToSource::to_source`] that allocates a
+    /// fresh `String`.
+    fn to_source_string(&self) -> String {
+        let mut out = String::new();
+        self.to_source(&mut out);
+        out
+    }
+}
+
+impl<I> ToSource for I
+where
+    I: MarkDownInput,
+{
+    fn to_source(&self, out: &mut String) {
+        out.push_str(self.as_str());
+    }
+}
+
+impl<T> ToSource for Option<T>
+where
+    T: ToSource,
+{
+    fn to_source(&self, out: &mut String) {
+        if let Some(value) = self {
+            value.to_source(out);
+        }
+    }
+}
+
+impl<T> ToSource for Vec<T>
+where
+    T: ToSource,
+{
+    fn to_source(&self, out: &mut String) {
+        for value in self {
+            value.to_source(out);
+        }
+    }
+}
+
+impl<T> ToSource for Box<T>
+where
+    T: ToSource,
+{
+    fn to_source(&self, out: &mut String) {
+        self.as_ref().to_source(out);
+    }
+}
+
+impl<T, P> ToSource for Punctuated<T, P>
+where
+    T: ToSource,
+    P: ToSource,
+{
+    fn to_source(&self, out: &mut String) {
+        for (item, punct) in &self.pairs {
+            item.to_source(out);
+            punct.to_source(out);
+        }
+
+        self.tail.to_source(out);
+    }
+}